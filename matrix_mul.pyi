@@ -0,0 +1,404 @@
+# Type stubs for the compiled `matrix_mul` extension. maturin ships this file
+# (plus a `py.typed` marker) alongside the shared library; keep it in sync with
+# the `#[pyfunction]` / `#[pymethods]` signatures in src/.
+
+from typing import (
+    Any,
+    Callable,
+    Dict,
+    Iterable,
+    Iterator,
+    List,
+    Literal,
+    Optional,
+    Sequence,
+    Tuple,
+    TypeVar,
+    Union,
+    overload,
+)
+
+import numpy as np
+from numpy.typing import NDArray
+
+_T = TypeVar("_T")
+_Rows = Sequence[Sequence[float]]
+_DTypeLike = Union[DType, Literal["float32", "float64"], None]
+
+__version__: str
+
+def version() -> str: ...
+def version_info() -> Tuple[int, int, int]: ...
+def sum_as_string(a: int, b: int) -> str: ...
+def sum_batch(values: Sequence[int]) -> List[str]: ...
+def triple(x: int) -> int: ...
+def triple_saturating(x: int) -> int: ...
+def triple_int(x: int) -> int: ...
+def triple_float(x: float, precision: Optional[int] = None) -> str: ...
+def risky_divide(a: float, b: float) -> float: ...
+def raise_custom() -> None: ...
+def call_methoda(obj: Any) -> Any: ...
+def dot(a: Sequence[float], b: Sequence[float]) -> float: ...
+# a Matrix if either operand is one, nested lists if both are lists
+@overload
+def matmul(a: _Rows, b: _Rows) -> List[List[float]]: ...
+@overload
+def matmul(a: Union[Matrix, _Rows], b: Union[Matrix, _Rows]) -> Matrix: ...
+@overload
+def matmul_parallel(a: _Rows, b: _Rows, num_threads: Optional[int] = None) -> List[List[float]]: ...
+@overload
+def matmul_parallel(
+    a: Union[Matrix, _Rows], b: Union[Matrix, _Rows], num_threads: Optional[int] = None
+) -> Matrix: ...
+def backend() -> str: ...
+def set_default_seed(seed: Optional[int]) -> None: ...
+def from_numpy(arr: Union[NDArray[np.float32], NDArray[np.float64]]) -> Matrix: ...
+def matmul_np(a: NDArray[np.float64], b: NDArray[np.float64]) -> NDArray[np.float64]: ...
+
+# exceptions
+
+class MatrixError(ValueError): ...
+
+class DimensionMismatchError(MatrixError):
+    @property
+    def lhs_shape(self) -> Optional[Tuple[int, ...]]: ...
+    @property
+    def rhs_shape(self) -> Optional[Tuple[int, ...]]: ...
+
+DimensionError = DimensionMismatchError
+
+class SingularMatrixError(MatrixError): ...
+class CustomError(MatrixError): ...
+
+# linear algebra
+
+class DType:
+    F32: DType
+    F64: DType
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+    def __int__(self) -> int: ...
+
+class Matrix:
+    @property
+    def rows(self) -> int: ...
+    @property
+    def cols(self) -> int: ...
+    @property
+    def shape(self) -> Tuple[int, int]: ...
+    @property
+    def T(self) -> Matrix: ...
+    @property
+    def dtype(self) -> DType: ...
+    def __init__(self, data: _Rows, dtype: _DTypeLike = None) -> None: ...
+    @classmethod
+    def from_list(cls, data: _Rows) -> Matrix: ...
+    @staticmethod
+    def identity(n: int, dtype: _DTypeLike = None) -> Matrix: ...
+    @staticmethod
+    def zeros(rows: int, cols: int, dtype: _DTypeLike = None) -> Matrix: ...
+    @staticmethod
+    def ones(rows: int, cols: int, dtype: _DTypeLike = None) -> Matrix: ...
+    @staticmethod
+    def full(rows: int, cols: int, value: float, dtype: _DTypeLike = None) -> Matrix: ...
+    @staticmethod
+    def random(
+        rows: int, cols: int, seed: Optional[int] = None, low: float = 0.0, high: float = 1.0
+    ) -> Matrix: ...
+    @staticmethod
+    def randn(rows: int, cols: int, seed: Optional[int] = None) -> Matrix: ...
+    @staticmethod
+    def vstack(mats: Sequence[Matrix]) -> Matrix: ...
+    @staticmethod
+    def hstack(mats: Sequence[Matrix]) -> Matrix: ...
+    def matmul(self, other: Matrix) -> Matrix: ...
+    def __matmul__(self, other: Matrix) -> Matrix: ...
+    def __add__(self, other: Union[Matrix, float]) -> Matrix: ...
+    def __sub__(self, other: Union[Matrix, float]) -> Matrix: ...
+    def __mul__(self, other: Union[Matrix, float]) -> Matrix: ...
+    def __rmul__(self, k: float) -> Matrix: ...
+    def __neg__(self) -> Matrix: ...
+    def __pow__(self, exp: int, modulo: None = None) -> Matrix: ...
+    def map(self, func: Callable[[float], float]) -> Matrix: ...
+    def map_inplace(self, func: Callable[[float], float]) -> None: ...
+    def row(self, i: int) -> List[float]: ...
+    def col(self, j: int) -> List[float]: ...
+    def get(self, i: int, j: int) -> float: ...
+    def set(self, i: int, j: int, value: float) -> None: ...
+    @overload
+    def __getitem__(self, index: Tuple[int, int]) -> float: ...
+    @overload
+    def __getitem__(self, index: Tuple[slice, slice]) -> Matrix: ...
+    @overload
+    def __getitem__(self, index: Union[Tuple[int, slice], Tuple[slice, int]]) -> List[float]: ...
+    def __setitem__(
+        self,
+        index: Tuple[Union[int, slice], Union[int, slice]],
+        value: Union[float, Matrix, Sequence[float], _Rows],
+    ) -> None: ...
+    @overload
+    def transpose(self, *, in_place: Literal[False] = False) -> Matrix: ...
+    @overload
+    def transpose(self, *, in_place: Literal[True]) -> None: ...
+    def reshape(self, rows: int, cols: int) -> Matrix: ...
+    def trace(self) -> float: ...
+    def norm(self, ord: Union[str, float, None] = None) -> float: ...
+    def determinant(self) -> float: ...
+    def det(self) -> float: ...
+    def inverse(self, *, tol: float = 1e-12) -> Matrix: ...
+    @overload
+    def solve(self, b: Vector, *, tol: float = 1e-12) -> Vector: ...
+    @overload
+    def solve(self, b: Sequence[float], *, tol: float = 1e-12) -> List[float]: ...
+    def to_list(self) -> List[List[float]]: ...
+    def __iter__(self) -> Iterator[List[float]]: ...
+    def __len__(self) -> int: ...
+    # a read-only view sharing memory with the matrix, not a copy
+    def to_numpy(self) -> Union[NDArray[np.float32], NDArray[np.float64]]: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def allclose(
+        self, other: Matrix, rtol: float = 1e-5, atol: float = 1e-8, equal_nan: bool = False
+    ) -> bool: ...
+    def __hash__(self) -> int: ...
+    def __copy__(self) -> Matrix: ...
+    def __deepcopy__(self, memo: Dict[int, Any]) -> Matrix: ...
+
+class SparseMatrix:
+    @property
+    def data(self) -> List[float]: ...
+    @property
+    def indices(self) -> List[int]: ...
+    @property
+    def indptr(self) -> List[int]: ...
+    @property
+    def nnz(self) -> int: ...
+    @property
+    def shape(self) -> Tuple[int, int]: ...
+    def __init__(
+        self,
+        entries: Union[Dict[Tuple[int, int], float], Iterable[Tuple[Tuple[int, int], float]]],
+        shape: Optional[Tuple[int, int]] = None,
+    ) -> None: ...
+    @staticmethod
+    def from_dense(m: Matrix, tol: float = 0.0) -> SparseMatrix: ...
+    def to_dense(self) -> Matrix: ...
+    @overload
+    def matmul(self, other: SparseMatrix) -> SparseMatrix: ...
+    @overload
+    def matmul(self, other: Matrix) -> Matrix: ...
+    @overload
+    def __matmul__(self, other: SparseMatrix) -> SparseMatrix: ...
+    @overload
+    def __matmul__(self, other: Matrix) -> Matrix: ...
+
+class Vector:
+    @property
+    def components(self) -> List[float]: ...
+    def __init__(self, components: Sequence[float]) -> None: ...
+    def dot(self, other: Vector) -> float: ...
+    def cross(self, other: Vector) -> Vector: ...
+    def magnitude(self) -> float: ...
+    def normalize(self) -> Vector: ...
+    def __add__(self, other: Vector) -> Vector: ...
+    def __sub__(self, other: Vector) -> Vector: ...
+    def __mul__(self, k: float) -> Vector: ...
+    def __rmul__(self, k: float) -> Vector: ...
+    def __len__(self) -> int: ...
+
+# enums
+
+class SampleEnum:
+    Firsty: SampleEnum
+    Secondy: SampleEnum
+    Lastly: SampleEnum
+    __members__: Dict[str, SampleEnum]
+    @property
+    def name(self) -> str: ...
+    @property
+    def value(self) -> int: ...
+    @classmethod
+    def variants(cls) -> List[SampleEnum]: ...
+    @classmethod
+    def values(cls) -> List[SampleEnum]: ...
+    @staticmethod
+    def from_name(name: str) -> SampleEnum: ...
+    @staticmethod
+    def from_str(s: str) -> SampleEnum: ...
+    @staticmethod
+    def from_value(v: int) -> SampleEnum: ...
+    def __int__(self) -> int: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+
+class Shape:
+    class Circle(Shape):
+        radius: float
+        def __init__(self, radius: float) -> None: ...
+
+    class Rectangle(Shape):
+        width: float
+        height: float
+        def __init__(self, width: float, height: float) -> None: ...
+
+    class Triangle(Shape):
+        a: float
+        b: float
+        c: float
+        def __init__(self, a: float, b: float, c: float) -> None: ...
+
+    @staticmethod
+    def circle(radius: float) -> Shape: ...
+    @staticmethod
+    def rectangle(width: float, height: float) -> Shape: ...
+    @staticmethod
+    def triangle(a: float, b: float, c: float) -> Shape: ...
+    @staticmethod
+    def from_dict(d: Dict[str, Any]) -> Shape: ...
+    def to_dict(self) -> Dict[str, Any]: ...
+    def area(self) -> float: ...
+    def perimeter(self) -> float: ...
+    def scale(self, factor: float) -> Shape: ...
+    def contains_point(self, x: float, y: float) -> bool: ...
+    def bounding_box(self) -> Tuple[float, float, float, float]: ...
+    def __eq__(self, other: object) -> bool: ...
+
+# classes
+
+class ClassOne:
+    id: int
+    def __init__(self, id: int) -> None: ...
+    def __lt__(self, other: ClassOne) -> bool: ...
+    def __le__(self, other: ClassOne) -> bool: ...
+    def __gt__(self, other: ClassOne) -> bool: ...
+    def __ge__(self, other: ClassOne) -> bool: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+    def __add__(self, other: ClassOne) -> ClassOne: ...
+    def __sub__(self, other: ClassOne) -> ClassOne: ...
+    def __neg__(self) -> ClassOne: ...
+    def __abs__(self) -> ClassOne: ...
+    def __int__(self) -> int: ...
+    def __float__(self) -> float: ...
+    @classmethod
+    def from_dict(cls, d: Dict[str, Any]) -> ClassOne: ...
+    def to_dict(self) -> Dict[str, Any]: ...
+
+class BoundedInt:
+    @property
+    def min(self) -> int: ...
+    @property
+    def max(self) -> int: ...
+    @property
+    def clamp(self) -> bool: ...
+    value: int
+    def __init__(self, value: int, min: int, max: int, *, clamp: bool = False) -> None: ...
+    def __add__(self, other: int) -> BoundedInt: ...
+    def __sub__(self, other: int) -> BoundedInt: ...
+    def __index__(self) -> int: ...
+
+class TupleClas:
+    @property
+    def value(self) -> str: ...
+    def __init__(self, value: str) -> None: ...
+    def __len__(self) -> int: ...
+    def __getitem__(self, index: int) -> str: ...
+    def __iter__(self) -> Iterator[str]: ...
+
+class BaseClass:
+    def __init__(self, id: int) -> None: ...
+    def methoda(self) -> int: ...
+    @classmethod
+    def from_string(cls: type[_T], s: str) -> _T: ...
+    @staticmethod
+    def default() -> BaseClass: ...
+    def __copy__(self) -> BaseClass: ...
+    def __deepcopy__(self, memo: Dict[int, Any]) -> BaseClass: ...
+
+class SubClassA(BaseClass):
+    id2: int
+    id: int
+    def __init__(self, id: int) -> None: ...
+    def full_id(self) -> str: ...
+    @classmethod
+    def depth(cls) -> int: ...
+    @classmethod
+    def from_base_id(cls: type[_T], id: int) -> _T: ...
+    @staticmethod
+    def default_multiplier() -> int: ...
+    def __copy__(self) -> SubClassA: ...
+    def __deepcopy__(self, memo: Dict[int, Any]) -> SubClassA: ...
+
+# submodules
+# a single-file stub can't declare real submodules, so `functions`,
+# `statistics` and `collections` are described as class namespaces; attribute
+# access (`matrix_mul.functions.gcd(...)`) type-checks as usual
+
+class functions:
+    @staticmethod
+    def func_null() -> int: ...
+    @staticmethod
+    def return_eins(num: int = -1) -> int: ...
+    @staticmethod
+    def return_either_or(num: Optional[int] = None) -> Optional[int]: ...
+    @staticmethod
+    def check_positive(x: int) -> None: ...
+    @staticmethod
+    def check_range(
+        x: _T, *, min: Optional[float] = None, max: Optional[float] = None, inclusive: bool = True
+    ) -> _T: ...
+    @staticmethod
+    def check_positive_float(x: float, epsilon: Optional[float] = None) -> None: ...
+    @staticmethod
+    def clamp(x: float, min: float, max: float) -> float: ...
+    @staticmethod
+    def lerp(a: float, b: float, t: float) -> float: ...
+    @staticmethod
+    def remap(x: float, in_min: float, in_max: float, out_min: float, out_max: float) -> float: ...
+    @staticmethod
+    def gcd(a: int, b: int) -> int: ...
+    @staticmethod
+    def lcm(a: int, b: int) -> int: ...
+    @staticmethod
+    def factorial(n: int) -> int: ...
+    @staticmethod
+    def fibonacci(n: int) -> int: ...
+    @staticmethod
+    def is_prime(n: int) -> bool: ...
+    @staticmethod
+    def prime_factors(n: int) -> List[int]: ...
+
+class statistics:
+    @staticmethod
+    def mean(data: Iterable[float]) -> float: ...
+    @staticmethod
+    def median(data: Iterable[float]) -> float: ...
+    @staticmethod
+    def quantile(data: Iterable[float], q: float) -> float: ...
+    @staticmethod
+    def variance(data: Iterable[float], ddof: Optional[int] = None) -> float: ...
+    @staticmethod
+    def stddev(data: Iterable[float], ddof: Optional[int] = None) -> float: ...
+    @staticmethod
+    def std_dev(data: Iterable[float], ddof: Optional[int] = None) -> float: ...
+    @staticmethod
+    def correlation(x: Iterable[float], y: Iterable[float]) -> float: ...
+
+class collections:
+    class Queue:
+        def __init__(self) -> None: ...
+        def push(self, item: Any) -> None: ...
+        def pop(self) -> Any: ...
+        def peek(self) -> Any: ...
+        def __len__(self) -> int: ...
+        def __bool__(self) -> bool: ...
+        def __iter__(self) -> Iterator[Any]: ...
+
+    class Stack:
+        def __init__(self) -> None: ...
+        def push(self, item: Any) -> None: ...
+        def pop(self) -> Any: ...
+        def peek(self) -> Any: ...
+        def __len__(self) -> int: ...
+        def __bool__(self) -> bool: ...
+        def __iter__(self) -> Iterator[Any]: ...
@@ -1,112 +1,1505 @@
+// pyo3 0.22's `#[pyfunction]` expansion trips clippy's `useless_conversion` on
+// every `PyResult` return type.
+#![allow(clippy::useless_conversion)]
+
 use core::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use pyo3::{
+    basic::CompareOp,
+    exceptions::{PyIndexError, PyKeyError, PyOverflowError, PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyDict, PyInt, PyString, PyTuple, PyType},
+};
+
+mod matrix;
+mod random;
+mod sparse;
+mod vector;
+
+use matrix::Matrix;
+use sparse::SparseMatrix;
+use vector::Vector;
+
+
+/// works for any Python ints, including negative and arbitrarily large ones
+/// anything that fits in i64 is added natively; bigger values fall back to
+/// Python's own int addition
+#[pyfunction]
+fn sum_as_string(a: &Bound<'_, PyInt>, b: &Bound<'_, PyInt>) -> PyResult<String> {
+    if let (Ok(x), Ok(y)) = (a.extract::<i64>(), b.extract::<i64>()) {
+        if let Some(sum) = x.checked_add(y) {
+            return Ok(sum.to_string());
+        }
+    }
+    Ok(a.add(b)?.str()?.to_string())
+}
+
+/// batches longer than this are summed with the GIL released
+const SUM_BATCH_GIL_THRESHOLD: usize = 1_000;
+
+/// `sum_as_string` over consecutive pairs, so `[1, 2, 3, 4]` gives
+/// `["3", "7"]`; sums are widened to u128 so they can't overflow
+/// the sums and their formatting happen without the GIL, which is only
+/// needed again to build the Python strings on return
+#[pyfunction]
+fn sum_batch(py: Python<'_>, values: Vec<usize>) -> PyResult<Vec<String>> {
+    if !values.len().is_multiple_of(2) {
+        return Err(PyValueError::new_err(format!(
+            "sum_batch needs an even number of values, got {}",
+            values.len()
+        )));
+    }
+    let sum_pairs = || {
+        values
+            .chunks_exact(2)
+            .map(|pair| (pair[0] as u128 + pair[1] as u128).to_string())
+            .collect()
+    };
+    if values.len() > SUM_BATCH_GIL_THRESHOLD {
+        Ok(py.allow_threads(sum_pairs))
+    } else {
+        Ok(sum_pairs())
+    }
+}
+
+/// fails with `CustomError` on division by zero, which
+/// `From<CustomError> for PyErr` turns into `matrix_mul.CustomError`
+#[pyfunction]
+fn risky_divide(a: f64, b: f64) -> Result<f64, CustomError> {
+    if b == 0.0 {
+        Err(CustomError)
+    } else {
+        Ok(a / b)
+    }
+}
+
+/// always fails, to demonstrate catching `matrix_mul.CustomError`
+#[pyfunction]
+fn raise_custom() -> Result<(), CustomError> {
+    Err(CustomError)
+}
+
+/// calls `obj.methoda()` through Python attribute lookup
+/// a Rust call like `BaseClass::methoda(&obj)` is static dispatch and always
+/// runs the Rust body; going through `call_method0` is virtual dispatch, so a
+/// Python subclass overriding `methoda` gets its override called
+#[pyfunction]
+fn call_methoda<'py>(obj: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    obj.call_method0("methoda")
+}
+
+/// either a `Matrix` or a nested list of rows
+#[derive(FromPyObject)]
+enum MatrixArg<'py> {
+    Matrix(PyRef<'py, Matrix>),
+    Rows(Vec<Vec<f64>>),
+}
+
+impl MatrixArg<'_> {
+    fn into_matrix(self) -> PyResult<Matrix> {
+        match self {
+            MatrixArg::Matrix(m) => Ok(m.clone()),
+            MatrixArg::Rows(rows) => Matrix::from_rows(rows),
+        }
+    }
+
+    fn is_matrix(&self) -> bool {
+        matches!(self, MatrixArg::Matrix(_))
+    }
+}
+
+/// multiplies two matrices
+/// returns a `Matrix` when either operand is a `Matrix` object and a nested
+/// list of rows when both are lists
+#[pyfunction]
+fn matmul(py: Python<'_>, a: MatrixArg<'_>, b: MatrixArg<'_>) -> PyResult<PyObject> {
+    match (a, b) {
+        (MatrixArg::Matrix(a), MatrixArg::Matrix(b)) => {
+            let (a, b) = (&*a, &*b);
+            Ok(py.allow_threads(|| a.multiply(b))?.into_py(py))
+        }
+        (a, b) => {
+            let as_matrix = a.is_matrix() || b.is_matrix();
+            let (a, b) = (a.into_matrix()?, b.into_matrix()?);
+            let product = py.allow_threads(|| a.multiply(&b))?;
+            Ok(product_into_py(py, product, as_matrix))
+        }
+    }
+}
+
+/// the product as a `Matrix`, or as a nested list of rows
+fn product_into_py(py: Python<'_>, product: Matrix, as_matrix: bool) -> PyObject {
+    if as_matrix {
+        product.into_py(py)
+    } else {
+        product.to_rows().into_py(py)
+    }
+}
+
+/// multiplies two matrices with the rows split across a rayon thread pool
+/// `num_threads` defaults to the number of logical CPUs; the result type
+/// follows the same rule as `matmul`
+#[pyfunction]
+#[pyo3(signature = (a, b, num_threads=None))]
+fn matmul_parallel(
+    py: Python<'_>,
+    a: MatrixArg<'_>,
+    b: MatrixArg<'_>,
+    num_threads: Option<i64>,
+) -> PyResult<PyObject> {
+    let pool = match num_threads {
+        None => None,
+        Some(n) if n <= 0 => {
+            return Err(PyValueError::new_err(format!(
+                "num_threads must be positive, got {}",
+                n
+            )))
+        }
+        Some(n) => Some(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(n as usize)
+                .build()
+                .map_err(|e| PyValueError::new_err(e.to_string()))?,
+        ),
+    };
+    let run = |a: &Matrix, b: &Matrix| {
+        py.allow_threads(|| match &pool {
+            Some(pool) => pool.install(|| a.multiply_parallel(b)),
+            None => a.multiply_parallel(b),
+        })
+    };
+
+    match (a, b) {
+        (MatrixArg::Matrix(a), MatrixArg::Matrix(b)) => Ok(run(&a, &b)?.into_py(py)),
+        (a, b) => {
+            let as_matrix = a.is_matrix() || b.is_matrix();
+            let product = run(&a.into_matrix()?, &b.into_matrix()?)?;
+            Ok(product_into_py(py, product, as_matrix))
+        }
+    }
+}
+
+/// Dense matrix and vector math implemented in Rust.
+///
+/// >>> from matrix_mul import Matrix
+/// >>> m = Matrix.from_list([[1.0, 2.0], [3.0, 4.0]])
+/// >>> (m @ m).to_list()
+/// [[7.0, 10.0], [15.0, 22.0]]
+#[pymodule(name = "matrix_mul")]
+mod matrix_mul {
+    use super::*;
+
+
+    #[pymodule_export]
+    use super::{call_methoda, raise_custom, risky_divide, sum_as_string, sum_batch};
+
+    #[pymodule_export]
+    use super::{matmul, matmul_parallel};
+
+    #[pymodule_export]
+    use super::Matrix;
+
+    #[pymodule_export]
+    use super::SparseMatrix;
+
+    #[pymodule_export]
+    use super::Vector;
+
+    #[pymodule_export]
+    use super::vector::dot;
+
+    #[pymodule_export]
+    use super::matrix::{backend, from_numpy, matmul_np, DType};
+
+    #[pymodule_export]
+    use super::random::set_default_seed;
+
+    #[pymodule_export]
+    use super::{BaseClass, BoundedInt, ClassOne, SampleEnum, Shape, SubClassA, TupleClas};
+
+    /// raises OverflowError if 3 * x doesn't fit in an i64
+    #[pyfunction]
+    fn triple(x: &Bound<'_, PyInt>) -> PyResult<i64> {
+        x.extract::<i64>()
+            .ok()
+            .and_then(|x| x.checked_mul(3))
+            .ok_or_else(|| PyOverflowError::new_err(format!("triple({}) overflows i64", x)))
+    }
+
+    /// like triple, but clamps to the i64 range instead of raising, for any
+    /// size of int
+    #[pyfunction]
+    fn triple_saturating(x: &Bound<'_, PyInt>) -> PyResult<i64> {
+        match x.extract::<i64>() {
+            Ok(x) => Ok(x.saturating_mul(3)),
+            Err(_) if x.lt(0)? => Ok(i64::MIN),
+            Err(_) => Ok(i64::MAX),
+        }
+    }
+
+    /// like triple, but declared with an i64 argument, so ints outside the
+    /// i64 range are rejected with OverflowError during conversion
+    #[pyfunction]
+    fn triple_int(x: i64) -> PyResult<i64> {
+        x.checked_mul(3)
+            .ok_or_else(|| PyOverflowError::new_err(format!("triple_int({}) overflows i64", x)))
+    }
+
+    /// 3 * x formatted with `precision` decimal places (6 by default);
+    /// raises OverflowError if a finite x triples past the f64 range
+    #[pyfunction]
+    #[pyo3(signature = (x, precision=None))]
+    fn triple_float(x: f64, precision: Option<u32>) -> PyResult<String> {
+        let tripled = x * 3.0;
+        if x.is_finite() && tripled.is_infinite() {
+            return Err(PyOverflowError::new_err(format!(
+                "triple_float({:?}) overflows f64",
+                x
+            )));
+        }
+        Ok(format!("{:.*}", precision.unwrap_or(6) as usize, tripled))
+    }
+
+    /// the crate version from Cargo.toml, e.g. "0.1.0"
+    #[pyfunction]
+    fn version() -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    /// `(major, minor, patch)`, like `sys.version_info`
+    #[pyfunction]
+    fn version_info() -> (u32, u32, u32) {
+        VERSION_INFO
+    }
+
+    const VERSION_INFO: (u32, u32, u32) = (
+        parse_version_part(env!("CARGO_PKG_VERSION_MAJOR")),
+        parse_version_part(env!("CARGO_PKG_VERSION_MINOR")),
+        parse_version_part(env!("CARGO_PKG_VERSION_PATCH")),
+    );
+
+    const fn parse_version_part(s: &str) -> u32 {
+        let bytes = s.as_bytes();
+        let mut n = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            assert!(bytes[i].is_ascii_digit(), "version parts must be numeric");
+            n = n * 10 + (bytes[i] - b'0') as u32;
+            i += 1;
+        }
+        n
+    }
+
+    #[pymodule]
+    mod functions {
+        use pyo3::{
+            exceptions::{PyOverflowError, PyValueError},
+            prelude::*,
+        };
+
+        #[pyfunction]
+        #[pyo3(name = "func_null")]
+        fn return_null() -> usize {
+            0
+        }
+
+        #[pyfunction]
+        #[pyo3(signature = (num=-1))]
+        fn return_eins(num: i32) -> i32 {
+            num
+        }
+
+        /// return None if no value is provided
+        #[pyfunction]
+        #[pyo3(signature = (num=None))]
+        fn return_either_or(num: Option<i32>) -> Option<i32> {
+            num
+        }
+
+        /// kept for backward compatibility, same as `check_range(x, min=0)`
+        #[pyfunction]
+        fn check_positive(x: i32) -> PyResult<()> {
+            check_bounds(x as f64, Some(0.0), None, true)
+        }
+
+        /// raises ValueError unless x lies within [min, max] (or (min, max)
+        /// with inclusive=False), where a missing bound is unbounded; returns
+        /// x itself so it can be used inline, e.g. `check_range(n, min=1) * 2`
+        #[pyfunction]
+        #[pyo3(signature = (x, *, min=None, max=None, inclusive=true))]
+        fn check_range<'py>(
+            x: &Bound<'py, PyAny>,
+            min: Option<f64>,
+            max: Option<f64>,
+            inclusive: bool,
+        ) -> PyResult<Bound<'py, PyAny>> {
+            check_bounds(x.extract()?, min, max, inclusive)?;
+            Ok(x.clone())
+        }
+
+        fn check_bounds(x: f64, min: Option<f64>, max: Option<f64>, inclusive: bool) -> PyResult<()> {
+            if min.is_some_and(f64::is_nan) || max.is_some_and(f64::is_nan) {
+                return Err(PyValueError::new_err("range bounds must not be NaN"));
+            }
+            if let (Some(lo), Some(hi)) = (min, max) {
+                if lo > hi {
+                    return Err(PyValueError::new_err(format!(
+                        "min ({:?}) is greater than max ({:?})",
+                        lo, hi
+                    )));
+                }
+            }
+            let (open, close) = if inclusive { ('[', ']') } else { ('(', ')') };
+            let interval = format!(
+                "{}{:?}, {:?}{}",
+                open,
+                min.unwrap_or(f64::NEG_INFINITY),
+                max.unwrap_or(f64::INFINITY),
+                close
+            );
+            if x.is_nan() {
+                return Err(PyValueError::new_err(format!(
+                    "x is NaN, which is never within {}",
+                    interval
+                )));
+            }
+            let (ge, le) = if inclusive { (">=", "<=") } else { (">", "<") };
+            if let Some(lo) = min {
+                if x < lo || (!inclusive && x == lo) {
+                    return Err(PyValueError::new_err(format!(
+                        "x violates the min bound of {}: expected x {} {:?}, got {:?}",
+                        interval, ge, lo, x
+                    )));
+                }
+            }
+            if let Some(hi) = max {
+                if x > hi || (!inclusive && x == hi) {
+                    return Err(PyValueError::new_err(format!(
+                        "x violates the max bound of {}: expected x {} {:?}, got {:?}",
+                        interval, le, hi, x
+                    )));
+                }
+            }
+            Ok(())
+        }
+
+        /// limits x to [min, max]
+        /// unlike `f64::clamp` this never panics: with min > max it returns max
+        /// a NaN x comes back as NaN, and a NaN bound raises ValueError
+        #[pyfunction]
+        fn clamp(x: f64, min: f64, max: f64) -> PyResult<f64> {
+            if min.is_nan() || max.is_nan() {
+                return Err(PyValueError::new_err("clamp bounds must not be NaN"));
+            }
+            if x.is_nan() {
+                return Ok(x);
+            }
+            Ok(x.max(min).min(max))
+        }
+
+        /// linear interpolation from a (t = 0) to b (t = 1)
+        #[pyfunction]
+        fn lerp(a: f64, b: f64, t: f64) -> f64 {
+            a + (b - a) * t
+        }
+
+        /// maps x from [in_min, in_max] onto [out_min, out_max]
+        #[pyfunction]
+        fn remap(x: f64, in_min: f64, in_max: f64, out_min: f64, out_max: f64) -> PyResult<f64> {
+            if in_min == in_max {
+                return Err(PyValueError::new_err(
+                    "input range is empty: in_min == in_max",
+                ));
+            }
+            Ok(lerp(out_min, out_max, (x - in_min) / (in_max - in_min)))
+        }
+
+        /// greatest common divisor, by the Euclidean algorithm
+        #[pyfunction]
+        fn gcd(mut a: u64, mut b: u64) -> u64 {
+            while b != 0 {
+                (a, b) = (b, a % b);
+            }
+            a
+        }
+
+        /// least common multiple; raises OverflowError if it doesn't fit in
+        /// 64 bits
+        #[pyfunction]
+        fn lcm(a: u64, b: u64) -> PyResult<u64> {
+            if a == 0 || b == 0 {
+                return Ok(0);
+            }
+            (a / gcd(a, b))
+                .checked_mul(b)
+                .ok_or_else(|| {
+                    PyOverflowError::new_err(format!("lcm({}, {}) overflows u64", a, b))
+                })
+        }
+
+        /// n!; raises ValueError for n > 20, the largest that fits in 64 bits
+        #[pyfunction]
+        fn factorial(n: u64) -> PyResult<u64> {
+            if n > 20 {
+                return Err(PyValueError::new_err(format!(
+                    "factorial({}) overflows u64, n must be at most 20",
+                    n
+                )));
+            }
+            Ok((1..=n).product())
+        }
+
+        /// the n-th Fibonacci number, with fibonacci(0) == 0; raises
+        /// OverflowError for n > 93, the largest that fits in 64 bits
+        #[pyfunction]
+        fn fibonacci(n: u64) -> PyResult<u64> {
+            // (F(i - 1), F(i)), starting from F(-1) = 1 so nothing past F(n)
+            // is ever computed
+            let (mut prev, mut cur) = (1u64, 0u64);
+            for _ in 0..n {
+                let next = prev.checked_add(cur).ok_or_else(|| {
+                    PyOverflowError::new_err(format!("fibonacci({}) overflows u64", n))
+                })?;
+                (prev, cur) = (cur, next);
+            }
+            Ok(cur)
+        }
+
+        /// inputs above this are trial-divided with the GIL released
+        const GIL_RELEASE_THRESHOLD: u64 = 1_000_000_000_000;
+
+        fn trial_division_is_prime(n: u64) -> bool {
+            if n < 4 {
+                return n >= 2;
+            }
+            if n.is_multiple_of(2) || n.is_multiple_of(3) {
+                return false;
+            }
+            // every prime above 3 is 6k - 1 or 6k + 1
+            let mut k = 5u64;
+            while k <= n / k {
+                if n.is_multiple_of(k) || n.is_multiple_of(k + 2) {
+                    return false;
+                }
+                k += 6;
+            }
+            true
+        }
+
+        fn trial_division_factors(mut n: u64) -> Vec<u64> {
+            let mut factors = Vec::new();
+            let mut d = 2u64;
+            while d <= n / d {
+                while n.is_multiple_of(d) {
+                    factors.push(d);
+                    n /= d;
+                }
+                d += if d == 2 { 1 } else { 2 };
+            }
+            if n > 1 {
+                factors.push(n);
+            }
+            factors
+        }
+
+        /// primality by trial division over 6k ± 1
+        #[pyfunction]
+        fn is_prime(py: Python<'_>, n: u64) -> bool {
+            if n > GIL_RELEASE_THRESHOLD {
+                py.allow_threads(|| trial_division_is_prime(n))
+            } else {
+                trial_division_is_prime(n)
+            }
+        }
+
+        /// prime factors in ascending order, with repetition, so
+        /// prime_factors(360) == [2, 2, 2, 3, 3, 5]
+        #[pyfunction]
+        fn prime_factors(py: Python<'_>, n: u64) -> Vec<u64> {
+            if n > GIL_RELEASE_THRESHOLD {
+                py.allow_threads(|| trial_division_factors(n))
+            } else {
+                trial_division_factors(n)
+            }
+        }
+
+        /// like check_positive, but values within `epsilon` below zero count
+        /// as zero to absorb floating-point rounding; a negative or NaN
+        /// `epsilon` raises ValueError
+        #[pyfunction]
+        #[pyo3(signature = (x, epsilon=None))]
+        fn check_positive_float(x: f64, epsilon: Option<f64>) -> PyResult<()> {
+            let epsilon = epsilon.unwrap_or(0.0);
+            if epsilon.is_nan() || epsilon < 0.0 {
+                Err(PyValueError::new_err(format!(
+                    "epsilon must be a non-negative number, got {:?}",
+                    epsilon
+                )))
+            } else if x.is_nan() {
+                Err(PyValueError::new_err("x is NaN"))
+            } else if x < -epsilon {
+                Err(PyValueError::new_err(format!(
+                    "x is negative: {:?} (epsilon {:?})",
+                    x, epsilon
+                )))
+            } else {
+                Ok(())
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::check_bounds;
+
+            #[test]
+            fn check_bounds_inclusive_and_exclusive() {
+                // (x, min, max, inclusive, accepted)
+                let cases = [
+                    (0.0, Some(0.0), Some(1.0), true, true),
+                    (1.0, Some(0.0), Some(1.0), true, true),
+                    (0.5, Some(0.0), Some(1.0), false, true),
+                    (0.0, Some(0.0), Some(1.0), false, false),
+                    (1.0, Some(0.0), Some(1.0), false, false),
+                    (-0.1, Some(0.0), Some(1.0), true, false),
+                    (1.1, Some(0.0), Some(1.0), true, false),
+                    (5.0, Some(5.0), None, true, true),
+                    (5.0, Some(5.0), None, false, false),
+                    (5.0, None, Some(5.0), true, true),
+                    (5.0, None, Some(5.0), false, false),
+                    (f64::MAX, None, None, false, true),
+                    (f64::NAN, None, None, true, false),
+                    (0.0, Some(1.0), Some(0.0), true, false),
+                    (0.0, Some(f64::NAN), None, true, false),
+                ];
+                for (x, min, max, inclusive, accepted) in cases {
+                    assert_eq!(
+                        check_bounds(x, min, max, inclusive).is_ok(),
+                        accepted,
+                        "check_bounds({:?}, {:?}, {:?}, {})",
+                        x,
+                        min,
+                        max,
+                        inclusive
+                    );
+                }
+            }
+        }
+    }
+
+    #[pymodule]
+    mod statistics {
+        use pyo3::{
+            exceptions::{PyDeprecationWarning, PyTypeError, PyValueError},
+            prelude::*,
+        };
+
+        use crate::DimensionMismatchError;
+
+        /// inputs longer than this are processed with the GIL released
+        const GIL_RELEASE_THRESHOLD: usize = 10_000;
+
+        fn maybe_allow_threads<T: Send>(
+            py: Python<'_>,
+            len: usize,
+            f: impl FnOnce() -> T + Send,
+        ) -> T {
+            if len > GIL_RELEASE_THRESHOLD {
+                py.allow_threads(f)
+            } else {
+                f()
+            }
+        }
+
+        /// collects any iterable of numbers, naming the index of the first
+        /// element that isn't one
+        fn numbers(data: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+            data.iter()?
+                .enumerate()
+                .map(|(i, item)| {
+                    let item = item?;
+                    item.extract().map_err(|_| {
+                        PyTypeError::new_err(format!(
+                            "element {} is not a number: {}",
+                            i,
+                            item.repr()
+                                .map_or_else(|_| "<unprintable>".to_string(), |r| r.to_string())
+                        ))
+                    })
+                })
+                .collect()
+        }
+
+        fn require_non_empty(data: &[f64], what: &str) -> PyResult<()> {
+            if data.is_empty() {
+                return Err(PyValueError::new_err(format!(
+                    "{} requires at least one data point",
+                    what
+                )));
+            }
+            Ok(())
+        }
+
+        fn mean_of(data: &[f64]) -> f64 {
+            data.iter().sum::<f64>() / data.len() as f64
+        }
+
+        /// sum of squared deviations from the mean, in one pass with
+        /// Welford's algorithm, which doesn't lose precision to cancellation
+        /// when the mean is large relative to the spread
+        fn sum_sq_dev(data: &[f64]) -> f64 {
+            let (mut mean, mut m2) = (0.0, 0.0);
+            for (i, x) in data.iter().enumerate() {
+                let delta = x - mean;
+                mean += delta / (i + 1) as f64;
+                m2 += delta * (x - mean);
+            }
+            m2
+        }
+
+        fn checked_variance(
+            py: Python<'_>,
+            data: Vec<f64>,
+            ddof: usize,
+            what: &str,
+        ) -> PyResult<f64> {
+            if data.len() <= ddof {
+                return Err(PyValueError::new_err(format!(
+                    "{} with ddof={} requires more than {} data points, got {}",
+                    what,
+                    ddof,
+                    ddof,
+                    data.len()
+                )));
+            }
+            Ok(maybe_allow_threads(py, data.len(), || {
+                sum_sq_dev(&data) / (data.len() - ddof) as f64
+            }))
+        }
+
+        #[pyfunction]
+        fn mean(
+            py: Python<'_>,
+            #[pyo3(from_py_with = "numbers")] data: Vec<f64>,
+        ) -> PyResult<f64> {
+            require_non_empty(&data, "mean")?;
+            Ok(maybe_allow_threads(py, data.len(), || mean_of(&data)))
+        }
+
+        /// for an even number of points, the mean of the two middle ones
+        #[pyfunction]
+        fn median(
+            py: Python<'_>,
+            #[pyo3(from_py_with = "numbers")] mut data: Vec<f64>,
+        ) -> PyResult<f64> {
+            require_non_empty(&data, "median")?;
+            Ok(maybe_allow_threads(py, data.len(), || {
+                data.sort_unstable_by(f64::total_cmp);
+                let mid = data.len() / 2;
+                if data.len().is_multiple_of(2) {
+                    (data[mid - 1] + data[mid]) / 2.0
+                } else {
+                    data[mid]
+                }
+            }))
+        }
+
+        /// the q-th quantile for q in [0, 1], interpolating linearly between
+        /// the two nearest data points as numpy's default method does
+        #[pyfunction]
+        fn quantile(
+            py: Python<'_>,
+            #[pyo3(from_py_with = "numbers")] mut data: Vec<f64>,
+            q: f64,
+        ) -> PyResult<f64> {
+            if !(0.0..=1.0).contains(&q) {
+                return Err(PyValueError::new_err(format!(
+                    "q must be within [0, 1], got {:?}",
+                    q
+                )));
+            }
+            require_non_empty(&data, "quantile")?;
+            Ok(maybe_allow_threads(py, data.len(), || {
+                data.sort_unstable_by(f64::total_cmp);
+                let h = (data.len() - 1) as f64 * q;
+                let (lo, frac) = (h.floor() as usize, h.fract());
+                if frac == 0.0 {
+                    data[lo]
+                } else {
+                    data[lo] + (data[lo + 1] - data[lo]) * frac
+                }
+            }))
+        }
+
+        /// population variance by default; pass ddof=1 for the sample
+        /// variance
+        #[pyfunction]
+        #[pyo3(signature = (data, ddof=None))]
+        fn variance(
+            py: Python<'_>,
+            #[pyo3(from_py_with = "numbers")] data: Vec<f64>,
+            ddof: Option<usize>,
+        ) -> PyResult<f64> {
+            checked_variance(py, data, ddof.unwrap_or(0), "variance")
+        }
+
+        /// population standard deviation by default; pass ddof=1 for the
+        /// sample standard deviation
+        #[pyfunction]
+        #[pyo3(signature = (data, ddof=None))]
+        fn stddev(
+            py: Python<'_>,
+            #[pyo3(from_py_with = "numbers")] data: Vec<f64>,
+            ddof: Option<usize>,
+        ) -> PyResult<f64> {
+            Ok(checked_variance(py, data, ddof.unwrap_or(0), "stddev")?.sqrt())
+        }
+
+        /// deprecated alias of `stddev`, kept for code written against the
+        /// original name
+        #[pyfunction]
+        #[pyo3(signature = (data, ddof=None))]
+        fn std_dev(
+            py: Python<'_>,
+            #[pyo3(from_py_with = "numbers")] data: Vec<f64>,
+            ddof: Option<usize>,
+        ) -> PyResult<f64> {
+            PyErr::warn_bound(
+                py,
+                &py.get_type_bound::<PyDeprecationWarning>(),
+                "statistics.std_dev is deprecated, use statistics.stddev",
+                1,
+            )?;
+            stddev(py, data, ddof)
+        }
+
+        /// Pearson correlation coefficient; raises ValueError if either
+        /// input is constant
+        #[pyfunction]
+        fn correlation(
+            py: Python<'_>,
+            #[pyo3(from_py_with = "numbers")] x: Vec<f64>,
+            #[pyo3(from_py_with = "numbers")] y: Vec<f64>,
+        ) -> PyResult<f64> {
+            if x.len() != y.len() {
+                return Err(DimensionMismatchError::with_shapes(
+                    format!(
+                        "cannot correlate sequences of length {} and {}",
+                        x.len(),
+                        y.len()
+                    ),
+                    &[x.len()],
+                    &[y.len()],
+                ));
+            }
+            require_non_empty(&x, "correlation")?;
+            let (cov, var_x, var_y) = maybe_allow_threads(py, x.len(), || {
+                let (mean_x, mean_y) = (mean_of(&x), mean_of(&y));
+                x.iter().zip(&y).fold((0.0, 0.0, 0.0), |(c, vx, vy), (a, b)| {
+                    let (dx, dy) = (a - mean_x, b - mean_y);
+                    (c + dx * dy, vx + dx * dx, vy + dy * dy)
+                })
+            });
+            if var_x == 0.0 || var_y == 0.0 {
+                return Err(PyValueError::new_err(
+                    "correlation is undefined for a constant sequence",
+                ));
+            }
+            Ok(cov / (var_x * var_y).sqrt())
+        }
+    }
+
+    #[pymodule]
+    mod collections {
+        use std::collections::VecDeque;
+
+        use pyo3::{exceptions::PyIndexError, prelude::*, PyTraverseError, PyVisit};
+
+        /// first-in first-out queue of arbitrary Python objects
+        #[pyclass(module = "matrix_mul.collections")]
+        #[derive(Default)]
+        struct Queue {
+            inner: VecDeque<PyObject>,
+        }
+
+        #[pymethods]
+        impl Queue {
+            #[new]
+            fn new() -> Self {
+                Queue::default()
+            }
+
+            /// adds `item` at the back
+            fn push(&mut self, item: PyObject) {
+                self.inner.push_back(item);
+            }
+
+            /// removes and returns the front item
+            fn pop(&mut self) -> PyResult<PyObject> {
+                self.inner
+                    .pop_front()
+                    .ok_or_else(|| PyIndexError::new_err("pop from an empty queue"))
+            }
+
+            /// the front item, without removing it
+            fn peek(&self, py: Python<'_>) -> PyResult<PyObject> {
+                self.inner
+                    .front()
+                    .map(|item| item.clone_ref(py))
+                    .ok_or_else(|| PyIndexError::new_err("peek at an empty queue"))
+            }
+
+            fn __len__(&self) -> usize {
+                self.inner.len()
+            }
+
+            fn __bool__(&self) -> bool {
+                !self.inner.is_empty()
+            }
+
+            /// front to back, i.e. the order `pop` would return them
+            fn __iter__(&self, py: Python<'_>) -> SnapshotIter {
+                SnapshotIter::new(py, self.inner.iter())
+            }
+
+            fn __repr__(&self) -> String {
+                format!("Queue(len={})", self.inner.len())
+            }
+
+            fn __traverse__(&self, visit: PyVisit<'_>) -> Result<(), PyTraverseError> {
+                self.inner.iter().try_for_each(|item| visit.call(item))
+            }
+
+            fn __clear__(&mut self) {
+                self.inner.clear();
+            }
+        }
+
+        /// last-in first-out stack of arbitrary Python objects
+        #[pyclass(module = "matrix_mul.collections")]
+        #[derive(Default)]
+        struct Stack {
+            inner: Vec<PyObject>,
+        }
+
+        #[pymethods]
+        impl Stack {
+            #[new]
+            fn new() -> Self {
+                Stack::default()
+            }
+
+            /// adds `item` on top
+            fn push(&mut self, item: PyObject) {
+                self.inner.push(item);
+            }
+
+            /// removes and returns the top item
+            fn pop(&mut self) -> PyResult<PyObject> {
+                self.inner
+                    .pop()
+                    .ok_or_else(|| PyIndexError::new_err("pop from an empty stack"))
+            }
+
+            /// the top item, without removing it
+            fn peek(&self, py: Python<'_>) -> PyResult<PyObject> {
+                self.inner
+                    .last()
+                    .map(|item| item.clone_ref(py))
+                    .ok_or_else(|| PyIndexError::new_err("peek at an empty stack"))
+            }
+
+            fn __len__(&self) -> usize {
+                self.inner.len()
+            }
+
+            fn __bool__(&self) -> bool {
+                !self.inner.is_empty()
+            }
+
+            /// top to bottom, i.e. the order `pop` would return them
+            fn __iter__(&self, py: Python<'_>) -> SnapshotIter {
+                SnapshotIter::new(py, self.inner.iter().rev())
+            }
+
+            fn __repr__(&self) -> String {
+                format!("Stack(len={})", self.inner.len())
+            }
+
+            fn __traverse__(&self, visit: PyVisit<'_>) -> Result<(), PyTraverseError> {
+                self.inner.iter().try_for_each(|item| visit.call(item))
+            }
+
+            fn __clear__(&mut self) {
+                self.inner.clear();
+            }
+        }
+
+        /// iterator over a copy of the items taken when iteration starts, so
+        /// pushing or popping meanwhile doesn't affect it
+        #[pyclass(module = "matrix_mul.collections")]
+        struct SnapshotIter {
+            items: std::vec::IntoIter<PyObject>,
+        }
+
+        impl SnapshotIter {
+            fn new<'a>(py: Python<'_>, items: impl Iterator<Item = &'a PyObject>) -> Self {
+                let items: Vec<PyObject> = items.map(|item| item.clone_ref(py)).collect();
+                SnapshotIter {
+                    items: items.into_iter(),
+                }
+            }
+        }
+
+        #[pymethods]
+        impl SnapshotIter {
+            fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+                slf
+            }
+
+            fn __next__(&mut self) -> Option<PyObject> {
+                self.items.next()
+            }
+
+            fn __traverse__(&self, visit: PyVisit<'_>) -> Result<(), PyTraverseError> {
+                self.items.as_slice().iter().try_for_each(|item| visit.call(item))
+            }
+
+            fn __clear__(&mut self) {
+                self.items = Vec::new().into_iter();
+            }
+        }
+    }
+
+    #[pymodule_init]
+    fn init(m: &Bound<'_, PyModule>) -> PyResult<()> {
+        m.add_class::<MatrixError>()?;
+        m.add_class::<DimensionMismatchError>()?;
+        m.add_class::<SingularMatrixError>()?;
+        m.add_class::<CustomException>()?;
+        // shorter alias for the common `except matrix_mul.DimensionError`
+        m.add("DimensionError", m.py().get_type_bound::<DimensionMismatchError>())?;
+        m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+        register_submodules(m, &["functions", "statistics", "collections"])
+    }
+
+    /// nested `#[pymodule]`s are only attributes of the parent, so
+    /// `import matrix_mul.functions` can't find them until they're put in
+    /// `sys.modules` under their dotted names
+    fn register_submodules(m: &Bound<'_, PyModule>, names: &[&str]) -> PyResult<()> {
+        let parent = m.name()?;
+        let sys_modules = PyModule::import_bound(m.py(), "sys")?.getattr("modules")?;
+        for name in names {
+            let submodule = m.getattr(*name)?;
+            let qualified = format!("{}.{}", parent, name);
+            submodule.setattr("__name__", &qualified)?;
+            submodule.setattr("__package__", &parent)?;
+            sys_modules.set_item(qualified, submodule)?;
+        }
+        Ok(())
+    }
+}
+
+
+#[derive(Debug)]
+struct CustomError;
+
+impl std::error::Error for CustomError {}
+
+impl fmt::Display for CustomError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "some error has occured")
+    }
+}
+
+impl std::convert::From<CustomError> for PyErr {
+    fn from(value: CustomError) -> Self {
+        PyErr::new::<CustomException, _>(value.to_string())
+    }
+}
+
+/// custom exceptions
+/// base class for every error raised by this library; it derives from
+/// ValueError so existing `except ValueError` handlers keep working
+#[pyclass(extends=PyValueError, subclass, module = "matrix_mul")]
+pub struct MatrixError {}
+
+#[pymethods]
+impl MatrixError {
+    // the message is stored by BaseException.__init__, which still runs
+    #[new]
+    #[pyo3(signature = (*_args))]
+    fn new(_args: &Bound<'_, PyTuple>) -> Self {
+        MatrixError {}
+    }
+}
+
+/// `(lhs_shape, rhs_shape)`, the pickled state of a DimensionMismatchError
+type OperandShapes = (Option<Vec<usize>>, Option<Vec<usize>>);
+
+/// raised when operand shapes are incompatible
+/// `lhs_shape` / `rhs_shape` hold the two offending shapes as tuples, or None
+/// when the error isn't about a pair of operands (e.g. a non-square matrix)
+#[pyclass(extends=MatrixError, module = "matrix_mul")]
+pub struct DimensionMismatchError {
+    lhs_shape: Option<Vec<usize>>,
+    rhs_shape: Option<Vec<usize>>,
+}
+
+impl DimensionMismatchError {
+    /// builds the exception with both shapes attached
+    /// the fields can only be filled in on a live instance, so this takes the
+    /// GIL, which is also safe from inside `allow_threads`
+    pub fn with_shapes(msg: String, lhs: &[usize], rhs: &[usize]) -> PyErr {
+        Python::with_gil(|py| {
+            let build = || -> PyResult<PyErr> {
+                let err = py
+                    .get_type_bound::<DimensionMismatchError>()
+                    .call1((msg,))?
+                    .downcast_into::<DimensionMismatchError>()?;
+                {
+                    let mut fields = err.borrow_mut();
+                    fields.lhs_shape = Some(lhs.to_vec());
+                    fields.rhs_shape = Some(rhs.to_vec());
+                }
+                Ok(PyErr::from_value_bound(err.into_any()))
+            };
+            build().unwrap_or_else(|e| e)
+        })
+    }
+}
+
+#[pymethods]
+impl DimensionMismatchError {
+    #[new]
+    #[pyo3(signature = (*args))]
+    fn new(args: &Bound<'_, PyTuple>) -> (Self, MatrixError) {
+        let fields = DimensionMismatchError {
+            lhs_shape: None,
+            rhs_shape: None,
+        };
+        (fields, MatrixError::new(args))
+    }
+
+    #[getter]
+    fn lhs_shape<'py>(&self, py: Python<'py>) -> Option<Bound<'py, PyTuple>> {
+        self.lhs_shape.as_ref().map(|shape| PyTuple::new_bound(py, shape))
+    }
+
+    #[getter]
+    fn rhs_shape<'py>(&self, py: Python<'py>) -> Option<Bound<'py, PyTuple>> {
+        self.rhs_shape.as_ref().map(|shape| PyTuple::new_bound(py, shape))
+    }
+
+    /// pickle support
+    /// `BaseException.__reduce__` only keeps `args`, so the shapes are passed
+    /// along as the state
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyType>, Bound<'py, PyAny>, OperandShapes)> {
+        let fields = slf.borrow();
+        Ok((
+            slf.get_type(),
+            slf.getattr("args")?,
+            (fields.lhs_shape.clone(), fields.rhs_shape.clone()),
+        ))
+    }
+
+    fn __setstate__(&mut self, state: &Bound<'_, PyAny>) -> PyResult<()> {
+        (self.lhs_shape, self.rhs_shape) = extract_state::<OperandShapes>(
+            state,
+            "DimensionMismatchError",
+            "an (lhs_shape, rhs_shape) tuple of int tuples or None",
+        )?;
+        Ok(())
+    }
+}
+
+/// the Python side of the Rust `CustomError`
+#[pyclass(extends=MatrixError, name = "CustomError", module = "matrix_mul")]
+pub struct CustomException {}
+
+#[pymethods]
+impl CustomException {
+    #[new]
+    #[pyo3(signature = (*args))]
+    fn new(args: &Bound<'_, PyTuple>) -> (Self, MatrixError) {
+        (CustomException {}, MatrixError::new(args))
+    }
+}
+
+/// raised when inverting or solving with a (numerically) singular matrix
+#[pyclass(extends=MatrixError, module = "matrix_mul")]
+pub struct SingularMatrixError {}
+
+#[pymethods]
+impl SingularMatrixError {
+    #[new]
+    #[pyo3(signature = (*args))]
+    fn new(args: &Bound<'_, PyTuple>) -> (Self, MatrixError) {
+        (SingularMatrixError {}, MatrixError::new(args))
+    }
+}
+
+/// enums
+/// enums.Enum
+/// unit-only enums
+#[pyclass(eq, eq_int, hash, frozen)]
+#[derive(PartialEq, Hash, Clone, Copy)]
+enum SampleEnum {
+    Firsty,
+    Secondy,
+    Lastly = 10,
+}
+
+impl SampleEnum {
+    /// every variant, in declaration order
+    const VARIANTS: [SampleEnum; 3] = [
+        SampleEnum::Firsty,
+        SampleEnum::Secondy,
+        SampleEnum::Lastly,
+    ];
+}
+
+#[pymethods]
+impl SampleEnum {
+    #[getter]
+    fn name(&self) -> &'static str {
+        match self {
+            SampleEnum::Firsty => "Firsty",
+            SampleEnum::Secondy => "Secondy",
+            SampleEnum::Lastly => "Lastly",
+        }
+    }
+
+    /// the discriminant, so `SampleEnum.Lastly.value == 10`
+    #[getter]
+    fn value(&self) -> i32 {
+        *self as i32
+    }
+
+    /// all members in declaration order
+    #[classmethod]
+    fn variants(_cls: &Bound<'_, PyType>) -> Vec<SampleEnum> {
+        SampleEnum::VARIANTS.to_vec()
+    }
+
+    /// name -> member mapping, like `enum.Enum.__members__`
+    #[classattr]
+    fn __members__(py: Python<'_>) -> PyResult<Py<PyDict>> {
+        let members = PyDict::new_bound(py);
+        for variant in SampleEnum::VARIANTS {
+            members.set_item(variant.name(), variant.into_py(py))?;
+        }
+        Ok(members.unbind())
+    }
 
-use pyo3::{exceptions::PyOSError, prelude::*};
+    #[staticmethod]
+    fn from_name(name: &str) -> PyResult<SampleEnum> {
+        SampleEnum::VARIANTS
+            .into_iter()
+            .find(|variant| variant.name() == name)
+            .ok_or_else(|| {
+                PyValueError::new_err(format!("no SampleEnum variant named {:?}", name))
+            })
+    }
 
+    /// like `from_name`, but ignores case, so `SampleEnum.from_str("lastly")`
+    /// is `Lastly`
+    #[staticmethod]
+    fn from_str(s: &str) -> PyResult<SampleEnum> {
+        SampleEnum::VARIANTS
+            .into_iter()
+            .find(|variant| variant.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| PyValueError::new_err(format!("no SampleEnum variant named {:?}", s)))
+    }
 
-#[pyfunction]
-fn sum_as_string(a: usize, b: usize) -> PyResult<String> {
-    Ok((a + b).to_string())
-}
+    /// the inverse of `value`, so `SampleEnum.from_value(10)` is `Lastly`
+    #[staticmethod]
+    fn from_value(v: i32) -> PyResult<SampleEnum> {
+        SampleEnum::VARIANTS
+            .into_iter()
+            .find(|variant| variant.value() == v)
+            .ok_or_else(|| PyValueError::new_err(format!("no SampleEnum variant with value {}", v)))
+    }
 
-#[pymodule(name = "matrix_mul")]
-mod matrix_mul {
-    use super::*;
+    /// every variant, for round-tripping with `value` / `from_value`
+    #[classmethod]
+    fn values(_cls: &Bound<'_, PyType>) -> Vec<SampleEnum> {
+        SampleEnum::VARIANTS.to_vec()
+    }
 
+    fn __repr__(&self) -> String {
+        format!("SampleEnum.{}", self.name())
+    }
 
-    #[pymodule_export]
-    use super::sum_as_string;
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
 
-    #[pyfunction]
-    fn triple(x: usize) -> usize {
-        x*3
+/// enums with structs and tuple variants
+/// TupleEnum
+/// `==` compares the variant and its dimensions
+#[pyclass(eq)]
+#[derive(PartialEq)]
+enum Shape{
+    Circle {radius: f32},
+    Rectangle {width: f32, height: f32},
+    Triangle {a: f32, b: f32, c: f32},
+}
+
+#[pymethods]
+impl Shape {
+    /// validated constructors
+    /// the generated `Shape.Circle(...)` / `Shape.Rectangle(...)` variant
+    /// constructors can't run custom checks, so these reject negative or NaN
+    /// dimensions (and impossible triangles) up front; shapes built the other
+    /// way are checked by `area`, `perimeter` and `scale` instead
+    #[staticmethod]
+    fn circle(radius: f32) -> PyResult<Shape> {
+        Shape::Circle { radius }.validated()
     }
 
-    #[pyfunction]
-    fn version() -> usize {
-        1
+    #[staticmethod]
+    fn rectangle(width: f32, height: f32) -> PyResult<Shape> {
+        Shape::Rectangle { width, height }.validated()
     }
 
-    #[pymodule]
-    mod functions {
-        use pyo3::{exceptions::PyValueError, prelude::*};
+    #[staticmethod]
+    fn triangle(a: f32, b: f32, c: f32) -> PyResult<Shape> {
+        Shape::Triangle { a, b, c }.validated()
+    }
 
-        #[pyfunction]
-        #[pyo3(name = "func_null")]
-        fn return_null() -> usize {
-            0
-        }
+    /// the inverse of `to_dict`, e.g. `{"kind": "circle", "radius": 2.0}`
+    #[staticmethod]
+    fn from_dict(d: &Bound<'_, PyDict>) -> PyResult<Shape> {
+        let field = |name: &str| -> PyResult<f32> {
+            match d.get_item(name)? {
+                Some(value) => value.extract(),
+                None => Err(PyKeyError::new_err(name.to_string())),
+            }
+        };
+        let kind: String = match d.get_item("kind")? {
+            Some(kind) => kind.extract()?,
+            None => return Err(PyKeyError::new_err("kind")),
+        };
+        let shape = match kind.as_str() {
+            "circle" => Shape::Circle {
+                radius: field("radius")?,
+            },
+            "rectangle" => Shape::Rectangle {
+                width: field("width")?,
+                height: field("height")?,
+            },
+            "triangle" => Shape::Triangle {
+                a: field("a")?,
+                b: field("b")?,
+                c: field("c")?,
+            },
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown shape kind {:?}",
+                    other
+                )))
+            }
+        };
+        shape.validated()
+    }
 
-        #[pyfunction]
-        #[pyo3(signature = (num=-1))]
-        fn return_eins(num: i32) -> i32 {
-            num
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        self.validate()?;
+        let d = PyDict::new_bound(py);
+        d.set_item("kind", self.name().to_lowercase())?;
+        for (name, value) in self.dimensions() {
+            d.set_item(name, value)?;
         }
+        Ok(d)
+    }
 
-        /// return None if no value is provided
-        #[pyfunction]
-        #[pyo3(signature = (num=None), text_signature = "None")]
-        fn return_either_or(num: Option<i32>) -> i32 {
-            num.unwrap_or(0)
+    fn area(&self) -> PyResult<f32> {
+        self.validate()?;
+        Ok(match self {
+            Shape::Circle { radius } => std::f32::consts::PI * radius * radius,
+            Shape::Rectangle { width, height } => width * height,
+            // Heron's formula
+            Shape::Triangle { a, b, c } => {
+                let s = (a + b + c) / 2.0;
+                (s * (s - a) * (s - b) * (s - c)).max(0.0).sqrt()
+            }
+        })
+    }
+
+    fn perimeter(&self) -> PyResult<f32> {
+        self.validate()?;
+        Ok(match self {
+            Shape::Circle { radius } => 2.0 * std::f32::consts::PI * radius,
+            Shape::Rectangle { width, height } => 2.0 * (width + height),
+            Shape::Triangle { a, b, c } => a + b + c,
+        })
+    }
+
+    /// a new shape of the same kind with every dimension multiplied by
+    /// `factor`, which must be positive
+    fn scale(&self, factor: f32) -> PyResult<Shape> {
+        self.validate()?;
+        if factor.is_nan() || factor <= 0.0 {
+            return Err(PyValueError::new_err(format!(
+                "scale factor must be positive, got {}",
+                factor
+            )));
         }
+        Ok(match *self {
+            Shape::Circle { radius } => Shape::Circle {
+                radius: radius * factor,
+            },
+            Shape::Rectangle { width, height } => Shape::Rectangle {
+                width: width * factor,
+                height: height * factor,
+            },
+            Shape::Triangle { a, b, c } => Shape::Triangle {
+                a: a * factor,
+                b: b * factor,
+                c: c * factor,
+            },
+        })
+    }
 
-        #[pyfunction]
-        fn check_positive(x: i32) -> PyResult<()> {
-            if x < 0 {
-                Err(PyValueError::new_err("x is negative"))
-            } else {
-                Ok(())
+    /// point-in-shape test, boundary included, with the shape centered on
+    /// the origin; see `triangle_vertices` for how a triangle is placed
+    fn contains_point(&self, x: f32, y: f32) -> PyResult<bool> {
+        self.validate()?;
+        Ok(match *self {
+            Shape::Circle { radius } => x * x + y * y <= radius * radius,
+            Shape::Rectangle { width, height } => x.abs() <= width / 2.0 && y.abs() <= height / 2.0,
+            Shape::Triangle { a, b, c } => {
+                let [p, q, r] = Shape::triangle_vertices(a, b, c);
+                // the vertices are computed, so points on an edge may land a
+                // rounding error off it
+                let tolerance = 4.0 * f32::EPSILON * (a + b + c) * (a + b + c);
+                // which side of each edge the point is on
+                let side = |(x0, y0): (f32, f32), (x1, y1): (f32, f32)| {
+                    (x1 - x0) * (y - y0) - (y1 - y0) * (x - x0)
+                };
+                let sides = [side(p, q), side(q, r), side(r, p)];
+                let has_neg = sides.iter().any(|&d| d < -tolerance);
+                let has_pos = sides.iter().any(|&d| d > tolerance);
+                !(has_neg && has_pos)
             }
-        }
+        })
     }
 
-    #[pymodule_init]
-    fn init(m: &Bound<'_, PyModule>) -> PyResult<()> {
-        m.add("version", m.getattr("version")?)
+    /// `(x_min, y_min, x_max, y_max)`, placed as in `contains_point`
+    fn bounding_box(&self) -> PyResult<(f32, f32, f32, f32)> {
+        self.validate()?;
+        Ok(match *self {
+            Shape::Circle { radius } => (-radius, -radius, radius, radius),
+            Shape::Rectangle { width, height } => {
+                (-width / 2.0, -height / 2.0, width / 2.0, height / 2.0)
+            }
+            Shape::Triangle { a, b, c } => Shape::triangle_vertices(a, b, c).iter().fold(
+                (f32::INFINITY, f32::INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+                |(x_min, y_min, x_max, y_max), &(x, y)| {
+                    (x_min.min(x), y_min.min(y), x_max.max(x), y_max.max(y))
+                },
+            ),
+        })
     }
-}
 
+    fn __repr__(&self) -> String {
+        let fields: Vec<String> = self
+            .dimensions()
+            .iter()
+            .map(|(name, value)| format!("{}={:?}", name, value))
+            .collect();
+        format!("Shape.{}({})", self.name(), fields.join(", "))
+    }
+}
 
-#[derive(Debug)]
-struct CustomError;
+impl Shape {
+    fn name(&self) -> &'static str {
+        match self {
+            Shape::Circle { .. } => "Circle",
+            Shape::Rectangle { .. } => "Rectangle",
+            Shape::Triangle { .. } => "Triangle",
+        }
+    }
 
-impl std::error::Error for CustomError {}
+    fn dimensions(&self) -> Vec<(&'static str, f32)> {
+        match self {
+            Shape::Circle { radius } => vec![("radius", *radius)],
+            Shape::Rectangle { width, height } => vec![("width", *width), ("height", *height)],
+            Shape::Triangle { a, b, c } => vec![("a", *a), ("b", *b), ("c", *c)],
+        }
+    }
 
-impl fmt::Display for CustomError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "some error has occured")
+    /// a triangle has no natural position, so side `c` lies along the x-axis,
+    /// the opposite vertex is above it (`b` from the left end, `a` from the
+    /// right), and the whole thing is shifted so the centroid is the origin
+    fn triangle_vertices(a: f32, b: f32, c: f32) -> [(f32, f32); 3] {
+        let apex_x = if c > 0.0 {
+            (b * b + c * c - a * a) / (2.0 * c)
+        } else {
+            0.0
+        };
+        let apex_y = (b * b - apex_x * apex_x).max(0.0).sqrt();
+        let (cx, cy) = ((c + apex_x) / 3.0, apex_y / 3.0);
+        [(-cx, -cy), (c - cx, -cy), (apex_x - cx, apex_y - cy)]
     }
-}
 
-impl std::convert::From<CustomError> for PyErr {
-    fn from(value: CustomError) -> Self {
-        PyOSError::new_err(value.to_string())
+    fn validated(self) -> PyResult<Shape> {
+        self.validate()?;
+        Ok(self)
     }
-}
 
-/// enums
-/// enums.Enum
-/// unit-only enums
-#[pyclass(eq, eq_int)]
-#[derive(PartialEq)]
-enum SampleEnum {
-    Firsty,
-    Secondy,
-    Lastly = 10,
+    /// raises ValueError for a negative or NaN dimension, or for triangle
+    /// sides that violate the triangle inequality
+    fn validate(&self) -> PyResult<()> {
+        for (name, value) in self.dimensions() {
+            if value.is_nan() || value < 0.0 {
+                return Err(PyValueError::new_err(format!(
+                    "{} must be a non-negative number, got {}",
+                    name, value
+                )));
+            }
+        }
+        if let Shape::Triangle { a, b, c } = *self {
+            if a > b + c || b > a + c || c > a + b {
+                return Err(PyValueError::new_err(format!(
+                    "sides {}, {}, {} violate the triangle inequality",
+                    a, b, c
+                )));
+            }
+        }
+        Ok(())
+    }
 }
 
-/// enums with structs and tuple variants
-/// TupleEnum
-#[pyclass]
-enum Shape{
-    Circle {radius: f32},
-    Rectangle {width: f32, height: f32},
+/// unpacks a `__setstate__` argument, turning a conversion failure into a
+/// TypeError that says which class was being unpickled
+fn extract_state<'py, T: FromPyObject<'py>>(
+    state: &Bound<'py, PyAny>,
+    class: &str,
+    expected: &str,
+) -> PyResult<T> {
+    state.extract().map_err(|_| {
+        PyTypeError::new_err(format!(
+            "invalid pickle state for {}: expected {}, got {}",
+            class,
+            expected,
+            state
+                .repr()
+                .map_or_else(|_| "<unprintable>".to_string(), |r| r.to_string())
+        ))
+    })
 }
 
-
 /// classes
 /// 
-#[pyclass]
+#[pyclass(module = "matrix_mul")]
+#[derive(Hash)]
 struct ClassOne {
+    #[pyo3(get, set)]
     id: i32
 }
 
@@ -116,6 +1509,196 @@ impl ClassOne {
     fn new(id: i32) -> Self {
         ClassOne { id }
     }
+
+    #[pyo3(name = "__repr__")]
+    fn repr(&self) -> String {
+        format!("ClassOne(id={})", self.id)
+    }
+
+    /// ordered by id; a non-ClassOne `other` fails extraction, which pyo3
+    /// turns into NotImplemented
+    fn __richcmp__(&self, other: PyRef<'_, Self>, op: CompareOp) -> bool {
+        op.matches(self.id.cmp(&other.id))
+    }
+
+    /// hashed from the id, as `Matrix` is from its elements: equal instances
+    /// collide, and assigning `c.id` on one that is already a dict key or
+    /// set member changes its hash, as with any mutable Python object
+    fn __hash__(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// arithmetic on the wrapped id; results that don't fit in an i32 raise
+    /// OverflowError instead of wrapping
+    #[pyo3(name = "__add__")]
+    fn add(&self, other: PyRef<'_, Self>) -> PyResult<ClassOne> {
+        ClassOne::checked(self.id.checked_add(other.id), "addition")
+    }
+
+    #[pyo3(name = "__sub__")]
+    fn sub(&self, other: PyRef<'_, Self>) -> PyResult<ClassOne> {
+        ClassOne::checked(self.id.checked_sub(other.id), "subtraction")
+    }
+
+    #[pyo3(name = "__neg__")]
+    fn neg(&self) -> PyResult<ClassOne> {
+        ClassOne::checked(self.id.checked_neg(), "negation")
+    }
+
+    #[pyo3(name = "__abs__")]
+    fn abs(&self) -> PyResult<ClassOne> {
+        ClassOne::checked(self.id.checked_abs(), "abs")
+    }
+
+    fn __int__(&self) -> i32 {
+        self.id
+    }
+
+    fn __float__(&self) -> f64 {
+        self.id as f64
+    }
+
+    /// builds a ClassOne from e.g. a deserialized JSON object
+    #[classmethod]
+    fn from_dict(_cls: &Bound<'_, PyType>, d: &Bound<'_, PyDict>) -> PyResult<ClassOne> {
+        match d.get_item("id")? {
+            Some(id) => Ok(ClassOne { id: id.extract()? }),
+            None => Err(PyKeyError::new_err("id")),
+        }
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let d = PyDict::new_bound(py);
+        d.set_item("id", self.id)?;
+        Ok(d)
+    }
+
+    /// pickle support
+    /// `#[new]` takes an argument, so pickle also needs `__getnewargs__`
+    fn __getnewargs__(&self) -> (i32,) {
+        (self.id,)
+    }
+
+    fn __getstate__(&self) -> i32 {
+        self.id
+    }
+
+    fn __setstate__(&mut self, state: &Bound<'_, PyAny>) -> PyResult<()> {
+        self.id = extract_state(state, "ClassOne", "an int id")?;
+        Ok(())
+    }
+}
+
+impl ClassOne {
+    fn checked(id: Option<i32>, op: &str) -> PyResult<ClassOne> {
+        id.map(|id| ClassOne { id })
+            .ok_or_else(|| PyOverflowError::new_err(format!("ClassOne {} overflows i32", op)))
+    }
+}
+
+/// property with validation
+/// an int confined to [min, max]; assigning to `value` outside the range
+/// raises ValueError, while `+` / `-` either raise or clamp depending on the
+/// `clamp` flag given at construction
+#[pyclass(module = "matrix_mul")]
+struct BoundedInt {
+    value: i64,
+    #[pyo3(get)]
+    min: i64,
+    #[pyo3(get)]
+    max: i64,
+    #[pyo3(get)]
+    clamp: bool,
+}
+
+#[pymethods]
+impl BoundedInt {
+    #[new]
+    #[pyo3(signature = (value, min, max, *, clamp=false))]
+    fn new(value: i64, min: i64, max: i64, clamp: bool) -> PyResult<Self> {
+        if min > max {
+            return Err(PyValueError::new_err(format!(
+                "min ({}) is greater than max ({})",
+                min, max
+            )));
+        }
+        let bounded = BoundedInt { value: min, min, max, clamp };
+        bounded.check(value)?;
+        Ok(BoundedInt { value, ..bounded })
+    }
+
+    #[getter]
+    fn get_value(&self) -> i64 {
+        self.value
+    }
+
+    /// never clamps, even with clamp=True: a bad assignment is a bug
+    #[setter]
+    fn set_value(&mut self, value: i64) -> PyResult<()> {
+        self.check(value)?;
+        self.value = value;
+        Ok(())
+    }
+
+    /// results keep the bounds and clamp flag of the left operand; a
+    /// BoundedInt on the right works too, through `__index__`
+    #[pyo3(name = "__add__")]
+    fn add(&self, other: i64) -> PyResult<BoundedInt> {
+        self.with_value(self.value as i128 + other as i128)
+    }
+
+    #[pyo3(name = "__sub__")]
+    fn sub(&self, other: i64) -> PyResult<BoundedInt> {
+        self.with_value(self.value as i128 - other as i128)
+    }
+
+    fn __index__(&self) -> i64 {
+        self.value
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "BoundedInt({}, min={}, max={}{})",
+            self.value,
+            self.min,
+            self.max,
+            if self.clamp { ", clamp=True" } else { "" }
+        )
+    }
+}
+
+impl BoundedInt {
+    fn check(&self, value: i64) -> PyResult<()> {
+        if value < self.min || value > self.max {
+            return Err(PyValueError::new_err(format!(
+                "{} is outside [{}, {}]",
+                value, self.min, self.max
+            )));
+        }
+        Ok(())
+    }
+
+    /// `value` is an i128 so that sums past the i64 range still clamp or
+    /// raise instead of wrapping
+    fn with_value(&self, value: i128) -> PyResult<BoundedInt> {
+        let (min, max) = (self.min as i128, self.max as i128);
+        let value = if self.clamp {
+            value.clamp(min, max)
+        } else if value < min || value > max {
+            return Err(PyValueError::new_err(format!(
+                "result {} is outside [{}, {}]",
+                value, self.min, self.max
+            )));
+        } else {
+            value
+        };
+        Ok(BoundedInt {
+            value: value as i64,
+            ..*self
+        })
+    }
 }
 
 /// tuple class
@@ -124,9 +1707,66 @@ impl ClassOne {
 #[pyclass]
 struct TupleClas(String);
 
+/// behaves like the one-element tuple `(value,)`, so it can be unpacked with
+/// `value, = TupleClas("x")`
+#[pymethods]
+impl TupleClas {
+    #[new]
+    fn new(value: String) -> Self {
+        TupleClas(value)
+    }
+
+    #[getter]
+    fn value(&self) -> &str {
+        &self.0
+    }
+
+    fn __len__(&self) -> usize {
+        1
+    }
+
+    /// only 0 and -1 are valid
+    fn __getitem__(&self, index: isize) -> PyResult<String> {
+        match index {
+            0 | -1 => Ok(self.0.clone()),
+            _ => Err(PyIndexError::new_err(format!(
+                "TupleClas index {} out of range",
+                index
+            ))),
+        }
+    }
+
+    fn __iter__(&self) -> TupleClasIter {
+        TupleClasIter {
+            items: vec![self.0.clone()].into_iter(),
+        }
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!("TupleClas({})", PyString::new_bound(py, &self.0).repr()?))
+    }
+}
+
+/// iterator returned by `TupleClas.__iter__`
+#[pyclass]
+struct TupleClasIter {
+    items: std::vec::IntoIter<String>,
+}
+
+#[pymethods]
+impl TupleClasIter {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> Option<String> {
+        self.items.next()
+    }
+}
+
 
 /// Inheritance
-#[pyclass(subclass)]
+#[pyclass(subclass, module = "matrix_mul")]
 struct BaseClass {
     id: i32
 }
@@ -142,20 +1782,80 @@ impl BaseClass {
     pub fn methoda(&self) -> PyResult<i32> {
         Ok(self.id)
     }
+
+    /// parses the id and calls `cls(id)`, so subclasses get instances of
+    /// themselves back
+    #[classmethod]
+    fn from_string<'py>(cls: &Bound<'py, PyType>, s: &str) -> PyResult<Bound<'py, PyAny>> {
+        let id: i32 = s
+            .trim()
+            .parse()
+            .map_err(|e| PyValueError::new_err(format!("invalid id {:?}: {}", s, e)))?;
+        cls.call1((id,))
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "default")]
+    fn default_py() -> Self {
+        BaseClass::new(0)
+    }
+
+    #[pyo3(name = "__repr__")]
+    fn repr(&self) -> String {
+        format!("BaseClass(id={})", self.id)
+    }
+
+    fn __getnewargs__(&self) -> (i32,) {
+        (self.id,)
+    }
+
+    fn __getstate__(&self) -> i32 {
+        self.id
+    }
+
+    fn __setstate__(&mut self, state: &Bound<'_, PyAny>) -> PyResult<()> {
+        self.id = extract_state(state, "BaseClass", "an int id")?;
+        Ok(())
+    }
+
+    /// copy support
+    /// there are no nested Python objects, so shallow and deep copies are
+    /// the same
+    fn __copy__(&self) -> BaseClass {
+        BaseClass { id: self.id }
+    }
+
+    fn __deepcopy__(&self, _memo: &Bound<'_, PyDict>) -> BaseClass {
+        self.__copy__()
+    }
 }
 
 
-#[pyclass(extends=BaseClass, subclass)]
+#[pyclass(extends=BaseClass, subclass, module = "matrix_mul")]
 struct SubClassA {
     #[pyo3(get, set)]
     id2: i32
 }
 
+impl SubClassA {
+    /// `id * default_multiplier()`, raising OverflowError if it doesn't fit
+    /// in an i32
+    fn scaled_id(id: i32) -> PyResult<i32> {
+        id.checked_mul(SubClassA::default_multiplier()).ok_or_else(|| {
+            PyOverflowError::new_err(format!(
+                "SubClassA id2 = {} * {} overflows i32",
+                id,
+                SubClassA::default_multiplier()
+            ))
+        })
+    }
+}
+
 #[pymethods]
 impl SubClassA {
     #[new]
-    fn new(id: i32) -> (Self, BaseClass) {
-        (SubClassA{id2: id*34}, BaseClass::new(id))
+    fn new(id: i32) -> PyResult<(Self, BaseClass)> {
+        Ok((SubClassA{id2: SubClassA::scaled_id(id)?}, BaseClass::new(id)))
     }
 
     pub fn methoda(self_: PyRef<'_, Self>) -> PyResult<i32> { //&self //&mut self
@@ -174,5 +1874,170 @@ impl SubClassA {
         Ok(())
     }
 
-    // #[classmethod] #[staticmethod]
-}
\ No newline at end of file
+    #[pyo3(name = "__repr__")]
+    fn repr(self_: PyRef<'_, Self>) -> String {
+        format!("SubClassA(id={}, id2={})", self_.as_super().id, self_.id2)
+    }
+
+    /// combines both struct levels, e.g. "base=5,sub=170"
+    fn full_id(self_: PyRef<'_, Self>) -> String {
+        let super_ = self_.as_super();
+        format!("base={},sub={}", super_.id, self_.id2)
+    }
+
+    /// number of classes from BaseClass down to `cls`, so 2 for SubClassA
+    /// and 3 for a Python subclass of it
+    #[classmethod]
+    fn depth(cls: &Bound<'_, PyType>) -> PyResult<usize> {
+        let base = cls.py().get_type_bound::<BaseClass>();
+        let mro = cls.getattr("__mro__")?.downcast_into::<PyTuple>()?;
+        Ok(mro.index(base)? + 1)
+    }
+
+    /// pickle support
+    /// the state spans both struct levels, so these override the BaseClass
+    /// versions and reach the parent's `id` through `as_super()`
+    fn __getnewargs__(self_: PyRef<'_, Self>) -> (i32,) {
+        (self_.as_super().id,)
+    }
+
+    fn __getstate__(self_: PyRef<'_, Self>) -> (i32, i32) {
+        (self_.as_super().id, self_.id2)
+    }
+
+    fn __setstate__(mut self_: PyRefMut<'_, Self>, state: &Bound<'_, PyAny>) -> PyResult<()> {
+        let (id, id2): (i32, i32) =
+            extract_state(state, "SubClassA", "an (id, id2) tuple of ints")?;
+        self_.id2 = id2;
+        self_.as_super().id = id;
+        Ok(())
+    }
+
+    /// copy support
+    /// returning `Self` would only allocate the SubClassA layer, so the
+    /// two-level object is built explicitly: `PyClassInitializer::from` the
+    /// base, `add_subclass` for this level, then `Py::new`
+    fn __copy__(self_: PyRef<'_, Self>) -> PyResult<Py<SubClassA>> {
+        let init = PyClassInitializer::from(BaseClass::new(self_.as_super().id))
+            .add_subclass(SubClassA { id2: self_.id2 });
+        Py::new(self_.py(), init)
+    }
+
+    fn __deepcopy__(self_: PyRef<'_, Self>, _memo: &Bound<'_, PyDict>) -> PyResult<Py<SubClassA>> {
+        SubClassA::__copy__(self_)
+    }
+
+    /// same as `cls(id)`; for SubClassA itself it's spelled out with
+    /// `PyClassInitializer` to show how the base and subclass layers are
+    /// assembled
+    /// only `#[new]` may return the initializer itself; anywhere else it has
+    /// to be turned into an object with `Bound::new`
+    #[classmethod]
+    fn from_base_id<'py>(cls: &Bound<'py, PyType>, id: i32) -> PyResult<Bound<'py, PyAny>> {
+        // the initializer only knows the Rust layers, so a Python subclass
+        // goes through its own constructor to get its `__init__` run
+        if !cls.is(&cls.py().get_type_bound::<SubClassA>()) {
+            return cls.call1((id,));
+        }
+        let init = PyClassInitializer::from(BaseClass::new(id))
+            .add_subclass(SubClassA { id2: SubClassA::scaled_id(id)? });
+        Ok(Bound::new(cls.py(), init)?.into_any())
+    }
+
+    /// the factor `new` applies to the base id to get `id2`
+    #[staticmethod]
+    fn default_multiplier() -> i32 {
+        34
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32) {
+        let tolerance = 1e-5 * expected.abs().max(1.0);
+        assert!((actual - expected).abs() <= tolerance, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn circle_area_and_perimeter() {
+        let circle = Shape::Circle { radius: 2.0 };
+        assert_close(circle.area().unwrap(), 4.0 * std::f32::consts::PI);
+        assert_close(circle.perimeter().unwrap(), 4.0 * std::f32::consts::PI);
+    }
+
+    #[test]
+    fn rectangle_area_and_perimeter() {
+        let rectangle = Shape::Rectangle { width: 3.0, height: 4.5 };
+        assert_close(rectangle.area().unwrap(), 13.5);
+        assert_close(rectangle.perimeter().unwrap(), 15.0);
+    }
+
+    #[test]
+    fn triangle_area_and_perimeter() {
+        let triangle = Shape::Triangle { a: 3.0, b: 4.0, c: 5.0 };
+        assert_close(triangle.area().unwrap(), 6.0);
+        assert_close(triangle.perimeter().unwrap(), 12.0);
+    }
+
+    #[test]
+    fn degenerate_triangle_has_zero_area() {
+        assert_close(Shape::Triangle { a: 1.0, b: 2.0, c: 3.0 }.area().unwrap(), 0.0);
+    }
+
+    #[test]
+    fn invalid_shapes_are_rejected() {
+        let shapes = [
+            Shape::Circle { radius: -1.0 },
+            Shape::Rectangle { width: f32::NAN, height: 1.0 },
+            Shape::Triangle { a: 1.0, b: 1.0, c: 10.0 },
+        ];
+        for shape in shapes {
+            assert!(shape.area().is_err());
+            assert!(shape.perimeter().is_err());
+            assert!(shape.contains_point(0.0, 0.0).is_err());
+            assert!(shape.bounding_box().is_err());
+        }
+    }
+
+    #[test]
+    fn circle_contains_point() {
+        let circle = Shape::Circle { radius: 2.0 };
+        // (x, y, inside)
+        let cases = [
+            (0.0, 0.0, true),
+            (1.0, 1.0, true),
+            (2.0, 0.0, true),
+            (0.0, -2.0, true),
+            (-2.0, 0.0, true),
+            (2.0, 0.001, false),
+            (1.5, 1.5, false),
+            (0.0, 2.001, false),
+        ];
+        for (x, y, inside) in cases {
+            assert_eq!(circle.contains_point(x, y).unwrap(), inside, "({}, {})", x, y);
+        }
+    }
+
+    #[test]
+    fn rectangle_contains_point() {
+        let rectangle = Shape::Rectangle { width: 4.0, height: 2.0 };
+        // (x, y, inside)
+        let cases = [
+            (0.0, 0.0, true),
+            (2.0, 0.0, true),
+            (-2.0, 0.0, true),
+            (0.0, 1.0, true),
+            (0.0, -1.0, true),
+            (2.0, 1.0, true),
+            (-2.0, -1.0, true),
+            (2.001, 0.0, false),
+            (0.0, 1.001, false),
+            (2.5, 1.5, false),
+        ];
+        for (x, y, inside) in cases {
+            assert_eq!(rectangle.contains_point(x, y).unwrap(), inside, "({}, {})", x, y);
+        }
+    }
+}
@@ -0,0 +1,1643 @@
+use ndarray::ArrayView2;
+use numpy::{IntoPyArray, PyArray2, PyReadonlyArray2};
+use pyo3::{
+    exceptions::{
+        PyBufferError, PyIndexError, PyMemoryError, PyRuntimeError, PyTypeError, PyValueError,
+    },
+    ffi,
+    prelude::*,
+    types::{PyDict, PySlice, PyType},
+};
+use rayon::prelude::*;
+use std::borrow::Cow;
+use std::collections::TryReserveError;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::ffi::{c_void, CStr};
+use std::os::raw::{c_char, c_int};
+
+use crate::random;
+use crate::vector::Vector;
+use crate::{DimensionMismatchError, SingularMatrixError};
+
+#[cfg(feature = "blas")]
+extern crate blas_src;
+
+/// output size (rows * cols) above which the native matmul goes parallel
+const PARALLEL_THRESHOLD: usize = 4096;
+
+/// default for how small a pivot (relative to the largest entry) may get
+/// before the matrix counts as singular
+const SINGULAR_EPSILON: f64 = 1e-12;
+
+/// rows printed by `str(m)` before the middle is elided
+const STR_MAX_ROWS: usize = 6;
+
+/// e.g. `mismatch_error("multiply", (2, 3), "by", (4, 5))` gives
+/// "cannot multiply 2x3 by 4x5"
+pub(crate) fn mismatch_error(
+    verb: &str,
+    lhs: (usize, usize),
+    joiner: &str,
+    rhs: (usize, usize),
+) -> PyErr {
+    DimensionMismatchError::with_shapes(
+        format!(
+            "cannot {} {}x{} {} {}x{}",
+            verb, lhs.0, lhs.1, joiner, rhs.0, rhs.1
+        ),
+        &[lhs.0, lhs.1],
+        &[rhs.0, rhs.1],
+    )
+}
+
+/// `vec![value; len]`, failing instead of aborting when it can't be allocated
+fn try_filled<T: Clone>(len: usize, value: T) -> Result<Vec<T>, TryReserveError> {
+    let mut data = Vec::new();
+    data.try_reserve_exact(len)?;
+    data.resize(len, value);
+    Ok(data)
+}
+
+/// a `dtype` argument: a `DType`, numpy's name for one ("float32" or
+/// "float64"), or None for the default
+fn dtype_arg(obj: &Bound<'_, PyAny>) -> PyResult<Option<DType>> {
+    if obj.is_none() {
+        return Ok(None);
+    }
+    if let Ok(dtype) = obj.extract::<DType>() {
+        return Ok(Some(dtype));
+    }
+    let name: String = obj.extract().map_err(|_| {
+        PyTypeError::new_err(format!(
+            "dtype must be a DType or a string, got {}",
+            obj.get_type()
+                .name()
+                .map_or_else(|_| "<unknown>".to_string(), |n| n.to_string())
+        ))
+    })?;
+    DType::from_name(&name).map(Some).ok_or_else(|| {
+        PyValueError::new_err(format!(
+            "unknown dtype {:?}, expected 'float32' or 'float64'",
+            name
+        ))
+    })
+}
+
+/// attaches `context` to an exception raised by Python code we called: as a
+/// note where exceptions have them (3.11+), otherwise by raising a
+/// RuntimeError from it
+fn with_note(py: Python<'_>, err: PyErr, context: String) -> PyErr {
+    match err.value_bound(py).call_method1("add_note", (&context,)) {
+        Ok(_) => err,
+        Err(_) => {
+            let wrapped = PyRuntimeError::new_err(context);
+            wrapped.set_cause(py, Some(err));
+            wrapped
+        }
+    }
+}
+
+/// resolves a possibly negative Python index against `len`
+fn wrap_index(index: isize, len: usize) -> Option<usize> {
+    let wrapped = if index < 0 {
+        index + len as isize
+    } else {
+        index
+    };
+    (0..len as isize)
+        .contains(&wrapped)
+        .then_some(wrapped as usize)
+}
+
+/// right-hand side of the elementwise operators
+#[derive(FromPyObject)]
+enum Operand<'py> {
+    Matrix(PyRef<'py, Matrix>),
+    Scalar(f64),
+}
+
+/// one axis of an `m[i, j]` subscript
+#[derive(FromPyObject)]
+enum AxisIndex<'py> {
+    Int(isize),
+    Slice(Bound<'py, PySlice>),
+}
+
+/// right-hand side of `m[i, j] = value` when slices are involved
+#[derive(FromPyObject)]
+enum SliceValue {
+    Scalar(f64),
+    Matrix(Matrix),
+    Rows(Vec<Vec<f64>>),
+    Flat(Vec<f64>),
+}
+
+/// `ord` argument of `Matrix.norm`, spelled as in `numpy.linalg.norm`:
+/// "fro", 1 or inf
+#[derive(FromPyObject)]
+enum NormOrd {
+    Name(String),
+    Number(f64),
+}
+
+/// right-hand side of `Matrix.solve`; the solution comes back in the same
+/// form
+#[derive(FromPyObject)]
+enum Rhs {
+    Vector(Vector),
+    List(Vec<f64>),
+}
+
+/// element type of a `Matrix`
+/// arithmetic always runs in f64; a float32 matrix halves the memory and
+/// rounds every stored result to f32; an operation between a float32 and a
+/// float64 matrix promotes to float64, as numpy does
+#[pyclass(eq, eq_int, hash, frozen, module = "matrix_mul")]
+#[derive(PartialEq, Hash, Clone, Copy)]
+pub enum DType {
+    F32,
+    F64,
+}
+
+impl DType {
+    fn promote(self, other: DType) -> DType {
+        if self == DType::F32 && other == DType::F32 {
+            DType::F32
+        } else {
+            DType::F64
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            DType::F32 => "float32",
+            DType::F64 => "float64",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<DType> {
+        match name {
+            "float32" => Some(DType::F32),
+            "float64" => Some(DType::F64),
+            _ => None,
+        }
+    }
+}
+
+#[pymethods]
+impl DType {
+    /// numpy's name for the type, e.g. "float32"
+    fn __str__(&self) -> &'static str {
+        self.name()
+    }
+}
+
+/// row-major element buffer of a `Matrix`
+#[derive(Clone)]
+pub enum Storage {
+    F32(Vec<f32>),
+    F64(Vec<f64>),
+}
+
+impl From<Vec<f64>> for Storage {
+    fn from(data: Vec<f64>) -> Self {
+        Storage::F64(data)
+    }
+}
+
+impl From<Vec<f32>> for Storage {
+    fn from(data: Vec<f32>) -> Self {
+        Storage::F32(data)
+    }
+}
+
+impl Storage {
+    fn dtype(&self) -> DType {
+        match self {
+            Storage::F32(_) => DType::F32,
+            Storage::F64(_) => DType::F64,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Storage::F32(data) => data.len(),
+            Storage::F64(data) => data.len(),
+        }
+    }
+
+    fn get(&self, k: usize) -> f64 {
+        match self {
+            Storage::F32(data) => data[k] as f64,
+            Storage::F64(data) => data[k],
+        }
+    }
+
+    fn set(&mut self, k: usize, value: f64) {
+        match self {
+            Storage::F32(data) => data[k] = value as f32,
+            Storage::F64(data) => data[k] = value,
+        }
+    }
+
+    /// every element as f64, widened one at a time rather than copied
+    fn iter(&self) -> impl Iterator<Item = f64> + '_ {
+        (0..self.len()).map(|k| self.get(k))
+    }
+
+    /// borrowed for float64, widened into a copy for float32
+    fn to_f64(&self) -> Cow<'_, [f64]> {
+        match self {
+            Storage::F32(data) => Cow::Owned(data.iter().map(|&x| x as f64).collect()),
+            Storage::F64(data) => Cow::Borrowed(data),
+        }
+    }
+
+    fn cast(self, dtype: DType) -> Storage {
+        match (self, dtype) {
+            (Storage::F64(data), DType::F32) => {
+                Storage::F32(data.into_iter().map(|x| x as f32).collect())
+            }
+            (Storage::F32(data), DType::F64) => {
+                Storage::F64(data.into_iter().map(|x| x as f64).collect())
+            }
+            (storage, _) => storage,
+        }
+    }
+}
+
+/// dense matrix stored row-major in a flat buffer
+#[pyclass(module = "matrix_mul")]
+pub struct Matrix {
+    data: Storage,
+    #[pyo3(get)]
+    rows: usize,
+    #[pyo3(get)]
+    cols: usize,
+    /// buffer-protocol views currently pointing into `data`; anything that
+    /// would reallocate `data` must raise BufferError while this is non-zero
+    /// atomic so the buffer slots only need a shared borrow, which unlike a
+    /// mutable one can't fail while another thread holds `&self`
+    exports: AtomicUsize,
+}
+
+impl Clone for Matrix {
+    fn clone(&self) -> Self {
+        Matrix::from_parts(self.data.clone(), self.rows, self.cols)
+    }
+}
+
+impl Matrix {
+    /// wraps a row-major buffer; `data.len()` must be `rows * cols`
+    pub fn from_parts(data: impl Into<Storage>, rows: usize, cols: usize) -> Self {
+        let data = data.into();
+        debug_assert_eq!(data.len(), rows * cols);
+        Matrix {
+            data,
+            rows,
+            cols,
+            exports: AtomicUsize::new(0),
+        }
+    }
+
+    /// the same matrix converted to `dtype`
+    fn with_dtype(self, dtype: DType) -> Matrix {
+        Matrix::from_parts(self.data.cast(dtype), self.rows, self.cols)
+    }
+
+    fn dtype(&self) -> DType {
+        self.data.dtype()
+    }
+
+    /// the elements as f64, which is what all the arithmetic runs on
+    pub(crate) fn values(&self) -> Cow<'_, [f64]> {
+        self.data.to_f64()
+    }
+
+    /// builds a matrix from a list of rows, rejecting ragged rows
+    pub fn from_rows(rows: Vec<Vec<f64>>) -> PyResult<Self> {
+        let cols = rows.first().map_or(0, |row| row.len());
+        let mut data = Vec::with_capacity(rows.len() * cols);
+        for (i, row) in rows.iter().enumerate() {
+            if row.len() != cols {
+                return Err(PyValueError::new_err(format!(
+                    "ragged matrix: row {} has {} columns, expected {}",
+                    i,
+                    row.len(),
+                    cols
+                )));
+            }
+            data.extend_from_slice(row);
+        }
+        Ok(Matrix::from_parts(data, rows.len(), cols))
+    }
+
+    /// backs the Python-side constructors: a `rows x cols` matrix with
+    /// every cell set to `value`, raising ValueError for non-positive or
+    /// overflowing dimensions and MemoryError when the buffer can't be
+    /// allocated, rather than panicking or aborting
+    fn filled(rows: isize, cols: isize, value: f64, dtype: DType) -> PyResult<Matrix> {
+        if rows <= 0 || cols <= 0 {
+            return Err(PyValueError::new_err(format!(
+                "matrix dimensions must be positive, got {}x{}",
+                rows, cols
+            )));
+        }
+        let (rows, cols) = (rows as usize, cols as usize);
+        let len = rows.checked_mul(cols).ok_or_else(|| {
+            PyValueError::new_err(format!("{}x{} matrix has too many elements", rows, cols))
+        })?;
+        let memory_error =
+            |_| PyMemoryError::new_err(format!("cannot allocate a {}x{} matrix", rows, cols));
+        // allocated at the final width, so float32 never needs the f64 size
+        let data = match dtype {
+            DType::F32 => Storage::F32(try_filled(len, value as f32).map_err(memory_error)?),
+            DType::F64 => Storage::F64(try_filled(len, value).map_err(memory_error)?),
+        };
+        Ok(Matrix::from_parts(data, rows, cols))
+    }
+
+    fn first_to_stack<'a>(mats: &'a [PyRef<'_, Matrix>], op: &str) -> PyResult<&'a Matrix> {
+        mats.first()
+            .map(|m| &**m)
+            .ok_or_else(|| PyValueError::new_err(format!("{} needs at least one matrix", op)))
+    }
+
+    /// float32 only if every stacked matrix is
+    fn stacked_dtype(mats: &[PyRef<'_, Matrix>]) -> DType {
+        mats.iter().fold(DType::F32, |dtype, m| dtype.promote(m.dtype()))
+    }
+
+    /// `func(x)` for each element of a snapshot of `slf`, taken so that
+    /// `func` can still read or write the matrix itself
+    fn call_elementwise(slf: &Bound<'_, Self>, func: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+        let py = slf.py();
+        let (values, cols) = {
+            let m = slf.borrow();
+            (m.values().into_owned(), m.cols)
+        };
+        values
+            .into_iter()
+            .enumerate()
+            .map(|(k, x)| {
+                let (i, j) = (k / cols, k % cols);
+                let result = func.call1((x,)).map_err(|err| {
+                    with_note(py, err, format!("while mapping element ({}, {})", i, j))
+                })?;
+                result.extract().map_err(|_| {
+                    PyTypeError::new_err(format!(
+                        "map function returned {} for element ({}, {}), expected a number",
+                        result
+                            .get_type()
+                            .name()
+                            .map_or_else(|_| "<unknown>".to_string(), |n| n.to_string()),
+                        i,
+                        j
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    /// overwrites every element, in row-major order, with draws from the
+    /// generator for `seed`
+    fn fill_with(&mut self, seed: Option<u64>, mut sample: impl FnMut(&mut random::Pcg32) -> f64) {
+        random::with_rng(seed, |rng| {
+            for k in 0..self.data.len() {
+                self.data.set(k, sample(rng));
+            }
+        })
+    }
+
+    fn fill_diagonal(&mut self, value: f64) {
+        for i in 0..self.rows.min(self.cols) {
+            self.data.set(i * self.cols + i, value);
+        }
+    }
+
+    fn require_square(&self, op: &str) -> PyResult<()> {
+        if self.rows != self.cols {
+            return Err(PyErr::new::<DimensionMismatchError, _>(format!(
+                "{} requires a square matrix, got {}x{}",
+                op, self.rows, self.cols
+            )));
+        }
+        Ok(())
+    }
+
+    /// applies `f` cell by cell against another matrix of the same shape or
+    /// against a scalar
+    fn elementwise(
+        &self,
+        other: Operand<'_>,
+        verb: &str,
+        f: impl Fn(f64, f64) -> f64,
+    ) -> PyResult<Matrix> {
+        let values = self.values();
+        let (data, dtype): (Vec<f64>, _) = match other {
+            Operand::Matrix(other) => {
+                if self.shape() != other.shape() {
+                    return Err(mismatch_error(verb, self.shape(), "and", other.shape()));
+                }
+                let data = values
+                    .iter()
+                    .zip(other.values().iter())
+                    .map(|(&a, &b)| f(a, b))
+                    .collect();
+                (data, self.dtype().promote(other.dtype()))
+            }
+            Operand::Scalar(b) => (values.iter().map(|&a| f(a, b)).collect(), self.dtype()),
+        };
+        Ok(Matrix::from_parts(data, self.rows, self.cols).with_dtype(dtype))
+    }
+
+    /// `f` applied to every element, keeping the dtype
+    fn mapped(&self, f: impl Fn(f64) -> f64) -> Matrix {
+        let data: Vec<f64> = self.values().iter().map(|&x| f(x)).collect();
+        Matrix::from_parts(data, self.rows, self.cols).with_dtype(self.dtype())
+    }
+
+    fn transposed(&self) -> Matrix {
+        let values = self.values();
+        let mut data: Vec<f64> = Vec::with_capacity(values.len());
+        for j in 0..self.cols {
+            data.extend(values.iter().skip(j).step_by(self.cols));
+        }
+        Matrix::from_parts(data, self.cols, self.rows).with_dtype(self.dtype())
+    }
+
+    /// flat offset of cell (i, j), bounds checked; negative indices count
+    /// back from the end as in Python
+    fn offset(&self, i: isize, j: isize) -> PyResult<usize> {
+        match (wrap_index(i, self.rows), wrap_index(j, self.cols)) {
+            (Some(r), Some(c)) => Ok(r * self.cols + c),
+            _ => Err(PyIndexError::new_err(format!(
+                "index ({}, {}) out of bounds for {}x{} matrix",
+                i, j, self.rows, self.cols
+            ))),
+        }
+    }
+
+    /// the row and column positions an `m[i, j]` subscript picks out
+    fn select(&self, (i, j): &(AxisIndex<'_>, AxisIndex<'_>)) -> PyResult<(Vec<usize>, Vec<usize>)> {
+        let positions = |axis: &AxisIndex<'_>, len: usize, name: &str| match axis {
+            AxisIndex::Int(k) => wrap_index(*k, len).map(|k| vec![k]).ok_or_else(|| {
+                PyIndexError::new_err(format!(
+                    "{} index {} out of bounds for {}x{} matrix",
+                    name, k, self.rows, self.cols
+                ))
+            }),
+            AxisIndex::Slice(slice) => {
+                let ix = slice.indices(len as isize)?;
+                Ok((0..ix.slicelength as isize)
+                    .map(|k| (ix.start + k * ix.step) as usize)
+                    .collect())
+            }
+        };
+        Ok((positions(i, self.rows, "row")?, positions(j, self.cols, "column")?))
+    }
+
+    /// the inverse of a square matrix; pure Rust, so callers can run it
+    /// with the GIL released
+    fn inverted(&self, tol: f64) -> PyResult<Matrix> {
+        let lu = Lu::new(self);
+        if lu.is_singular(tol) {
+            return Err(lu.singular_error());
+        }
+
+        let n = self.rows;
+        let mut data = vec![0.0; n * n];
+        let mut e = vec![0.0; n];
+        for j in 0..n {
+            e[j] = 1.0;
+            for (i, x) in lu.solve(&e).into_iter().enumerate() {
+                data[i * n + j] = x;
+            }
+            e[j] = 0.0;
+        }
+        Ok(Matrix::from_parts(data, n, n).with_dtype(self.dtype()))
+    }
+
+    pub fn to_rows(&self) -> Vec<Vec<f64>> {
+        if self.cols == 0 {
+            return vec![Vec::new(); self.rows];
+        }
+        self.values()
+            .chunks(self.cols)
+            .map(|row| row.to_vec())
+            .collect()
+    }
+
+    pub fn multiply(&self, other: &Matrix) -> PyResult<Matrix> {
+        self.product(other, |out| self.multiply_into(other, out))
+    }
+
+    /// like `multiply`, but always splits the rows across the current rayon
+    /// pool regardless of size or backend
+    pub fn multiply_parallel(&self, other: &Matrix) -> PyResult<Matrix> {
+        self.product(other, |out| self.multiply_native(other, out, true))
+    }
+
+    /// checks the shapes and allocates the output, leaving `fill` to compute
+    /// into the zeroed buffer; the result has the promoted dtype
+    fn product(&self, other: &Matrix, fill: impl FnOnce(&mut [f64])) -> PyResult<Matrix> {
+        if self.cols != other.rows {
+            return Err(mismatch_error(
+                "multiply",
+                self.shape(),
+                "by",
+                other.shape(),
+            ));
+        }
+
+        let mut data = vec![0.0; self.rows * other.cols];
+        // an empty inner dimension leaves the product all zeros, and BLAS
+        // rejects zero leading dimensions anyway
+        if self.cols > 0 && other.cols > 0 {
+            fill(&mut data);
+        }
+        let dtype = self.dtype().promote(other.dtype());
+        Ok(Matrix::from_parts(data, self.rows, other.cols).with_dtype(dtype))
+    }
+
+    #[cfg(not(feature = "blas"))]
+    fn multiply_into(&self, other: &Matrix, out: &mut [f64]) {
+        let parallel = self.rows * other.cols > PARALLEL_THRESHOLD;
+        self.multiply_native(other, out, parallel);
+    }
+
+    /// standard triple loop, ordered i-k-j so the inner loop walks both
+    /// buffers contiguously; with `parallel` each output row becomes a
+    /// rayon task
+    /// float32 operands are widened element by element in the loop, never
+    /// copied whole
+    fn multiply_native(&self, other: &Matrix, out: &mut [f64], parallel: bool) {
+        let n = other.cols;
+        match (&self.data, &other.data) {
+            (Storage::F64(a), Storage::F64(b)) => self.multiply_slices(a, b, n, out, parallel),
+            (Storage::F64(a), Storage::F32(b)) => self.multiply_slices(a, b, n, out, parallel),
+            (Storage::F32(a), Storage::F64(b)) => self.multiply_slices(a, b, n, out, parallel),
+            (Storage::F32(a), Storage::F32(b)) => self.multiply_slices(a, b, n, out, parallel),
+        }
+    }
+
+    /// `multiply_native` for one combination of operand dtypes; `a` is this
+    /// matrix's buffer and `b` the `n`-column right-hand side's
+    fn multiply_slices<A, B>(&self, a: &[A], b: &[B], n: usize, out: &mut [f64], parallel: bool)
+    where
+        A: Copy + Into<f64> + Sync,
+        B: Copy + Into<f64> + Sync,
+    {
+        let kernel = |(i, out_row): (usize, &mut [f64])| {
+            for k in 0..self.cols {
+                let a_ik: f64 = a[i * self.cols + k].into();
+                let b_row = &b[k * n..(k + 1) * n];
+                for (out, &b_kj) in out_row.iter_mut().zip(b_row) {
+                    *out += a_ik * b_kj.into();
+                }
+            }
+        };
+        if parallel {
+            out.par_chunks_mut(n).enumerate().for_each(kernel);
+        } else {
+            out.chunks_mut(n).enumerate().for_each(kernel);
+        }
+    }
+
+    /// `dgemm` for two float64 operands; anything involving float32 would
+    /// need a widened copy first, so it takes the native kernel instead
+    #[cfg(feature = "blas")]
+    fn multiply_into(&self, other: &Matrix, out: &mut [f64]) {
+        use cblas::{Layout, Transpose};
+
+        let (Storage::F64(a), Storage::F64(b)) = (&self.data, &other.data) else {
+            let parallel = self.rows * other.cols > PARALLEL_THRESHOLD;
+            return self.multiply_native(other, out, parallel);
+        };
+        let (m, k, n) = (self.rows as i32, self.cols as i32, other.cols as i32);
+        // SAFETY: all three buffers are row-major with exactly the
+        // dimensions passed, checked by the caller
+        unsafe {
+            cblas::dgemm(
+                Layout::RowMajor,
+                Transpose::None,
+                Transpose::None,
+                m,
+                n,
+                k,
+                1.0,
+                a,
+                k,
+                b,
+                n,
+                0.0,
+                out,
+                n,
+            );
+        }
+    }
+}
+
+/// LU decomposition with partial pivoting, `P A = L U`
+/// `L` (unit diagonal, not stored) and `U` share one row-major buffer
+struct Lu {
+    lu: Vec<f64>,
+    n: usize,
+    /// row `i` of `P A` is row `perm[i]` of `A`
+    perm: Vec<usize>,
+    /// +1 or -1 depending on the parity of the row swaps
+    sign: f64,
+    /// largest absolute entry of `A`, to scale the singularity check
+    scale: f64,
+}
+
+impl Lu {
+    fn new(a: &Matrix) -> Lu {
+        let n = a.rows;
+        let mut lu = a.values().into_owned();
+        let mut perm: Vec<usize> = (0..n).collect();
+        let mut sign = 1.0;
+        let scale = lu.iter().fold(0.0_f64, |m, x| m.max(x.abs()));
+
+        for k in 0..n {
+            let p = (k..n)
+                .max_by(|&i, &j| lu[i * n + k].abs().total_cmp(&lu[j * n + k].abs()))
+                .unwrap_or(k);
+            if p != k {
+                for j in 0..n {
+                    lu.swap(k * n + j, p * n + j);
+                }
+                perm.swap(k, p);
+                sign = -sign;
+            }
+
+            let pivot = lu[k * n + k];
+            if pivot == 0.0 {
+                continue;
+            }
+            for i in k + 1..n {
+                let factor = lu[i * n + k] / pivot;
+                lu[i * n + k] = factor;
+                for j in k + 1..n {
+                    lu[i * n + j] -= factor * lu[k * n + j];
+                }
+            }
+        }
+        Lu {
+            lu,
+            n,
+            perm,
+            sign,
+            scale,
+        }
+    }
+
+    fn determinant(&self) -> f64 {
+        (0..self.n).fold(self.sign, |det, i| det * self.lu[i * self.n + i])
+    }
+
+    /// `tol` is relative to the largest entry of `A`
+    fn is_singular(&self, tol: f64) -> bool {
+        let tolerance = tol * self.scale;
+        (0..self.n).any(|i| self.lu[i * self.n + i].abs() <= tolerance)
+    }
+
+    fn singular_error(&self) -> PyErr {
+        PyErr::new::<SingularMatrixError, _>(format!("{}x{} matrix is singular", self.n, self.n))
+    }
+
+    /// solves `A x = b`; only meaningful when the matrix is not singular
+    fn solve(&self, b: &[f64]) -> Vec<f64> {
+        let n = self.n;
+        let mut x: Vec<f64> = self.perm.iter().map(|&p| b[p]).collect();
+        for i in 0..n {
+            for j in 0..i {
+                x[i] -= self.lu[i * n + j] * x[j];
+            }
+        }
+        for i in (0..n).rev() {
+            for j in i + 1..n {
+                x[i] -= self.lu[i * n + j] * x[j];
+            }
+            x[i] /= self.lu[i * n + i];
+        }
+        x
+    }
+}
+
+#[pymethods]
+impl Matrix {
+    /// `dtype` is a `DType` or "float32" / "float64", defaulting to float64
+    #[new]
+    #[pyo3(signature = (data, dtype=None))]
+    fn new(
+        data: Vec<Vec<f64>>,
+        #[pyo3(from_py_with = "dtype_arg")] dtype: Option<DType>,
+    ) -> PyResult<Self> {
+        Ok(Matrix::from_rows(data)?.with_dtype(dtype.unwrap_or(DType::F64)))
+    }
+
+    /// element type; results of mixing float32 and float64 are float64
+    #[getter(dtype)]
+    fn get_dtype(&self) -> DType {
+        self.dtype()
+    }
+
+    /// same as `Matrix(data)`; rows must all have the same length
+    #[classmethod]
+    fn from_list(_cls: &Bound<'_, PyType>, data: Vec<Vec<f64>>) -> PyResult<Matrix> {
+        Matrix::from_rows(data)
+    }
+
+    /// direct constructors
+    /// these allocate in Rust without an intermediate nested list; every
+    /// dimension must be positive, and `dtype` works as in the constructor
+    #[staticmethod]
+    #[pyo3(signature = (n, dtype=None))]
+    fn identity(
+        n: isize,
+        #[pyo3(from_py_with = "dtype_arg")] dtype: Option<DType>,
+    ) -> PyResult<Matrix> {
+        let mut m = Matrix::filled(n, n, 0.0, dtype.unwrap_or(DType::F64))?;
+        m.fill_diagonal(1.0);
+        Ok(m)
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (rows, cols, dtype=None))]
+    fn zeros(
+        rows: isize,
+        cols: isize,
+        #[pyo3(from_py_with = "dtype_arg")] dtype: Option<DType>,
+    ) -> PyResult<Matrix> {
+        Matrix::filled(rows, cols, 0.0, dtype.unwrap_or(DType::F64))
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (rows, cols, dtype=None))]
+    fn ones(
+        rows: isize,
+        cols: isize,
+        #[pyo3(from_py_with = "dtype_arg")] dtype: Option<DType>,
+    ) -> PyResult<Matrix> {
+        Matrix::filled(rows, cols, 1.0, dtype.unwrap_or(DType::F64))
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (rows, cols, value, dtype=None))]
+    fn full(
+        rows: isize,
+        cols: isize,
+        value: f64,
+        #[pyo3(from_py_with = "dtype_arg")] dtype: Option<DType>,
+    ) -> PyResult<Matrix> {
+        Matrix::filled(rows, cols, value, dtype.unwrap_or(DType::F64))
+    }
+
+    /// uniform samples from `[low, high)`
+    /// an explicit `seed` gives the same matrix on every call and platform;
+    /// without one the generator from `set_default_seed` is used, or a
+    /// randomly seeded one
+    #[staticmethod]
+    #[pyo3(signature = (rows, cols, seed=None, low=0.0, high=1.0))]
+    fn random(
+        py: Python<'_>,
+        rows: isize,
+        cols: isize,
+        seed: Option<u64>,
+        low: f64,
+        high: f64,
+    ) -> PyResult<Matrix> {
+        if !(low.is_finite() && high.is_finite()) || low >= high {
+            return Err(PyValueError::new_err(format!(
+                "random needs finite low < high, got low={:?}, high={:?}",
+                low, high
+            )));
+        }
+        let mut m = Matrix::filled(rows, cols, 0.0, DType::F64)?;
+        py.allow_threads(|| m.fill_with(seed, |rng| rng.uniform(low, high)));
+        Ok(m)
+    }
+
+    /// standard normal samples; `seed` works as for `random`
+    #[staticmethod]
+    #[pyo3(signature = (rows, cols, seed=None))]
+    fn randn(py: Python<'_>, rows: isize, cols: isize, seed: Option<u64>) -> PyResult<Matrix> {
+        let mut m = Matrix::filled(rows, cols, 0.0, DType::F64)?;
+        py.allow_threads(|| m.fill_with(seed, random::Pcg32::standard_normal));
+        Ok(m)
+    }
+
+    /// stacks matrices top to bottom; they must all have the same number
+    /// of columns
+    #[staticmethod]
+    fn vstack(mats: Vec<PyRef<'_, Matrix>>) -> PyResult<Matrix> {
+        let first = Matrix::first_to_stack(&mats, "vstack")?;
+        if let Some(m) = mats.iter().find(|m| m.cols != first.cols) {
+            return Err(mismatch_error("vstack", first.shape(), "with", m.shape()));
+        }
+        let rows = mats.iter().map(|m| m.rows).sum();
+        let mut data = Vec::with_capacity(rows * first.cols);
+        for m in &mats {
+            data.extend_from_slice(&m.values());
+        }
+        Ok(Matrix::from_parts(data, rows, first.cols).with_dtype(Matrix::stacked_dtype(&mats)))
+    }
+
+    /// stacks matrices left to right, e.g. the augmented `[A | b]`; they
+    /// must all have the same number of rows
+    #[staticmethod]
+    fn hstack(mats: Vec<PyRef<'_, Matrix>>) -> PyResult<Matrix> {
+        let first = Matrix::first_to_stack(&mats, "hstack")?;
+        if let Some(m) = mats.iter().find(|m| m.rows != first.rows) {
+            return Err(mismatch_error("hstack", first.shape(), "with", m.shape()));
+        }
+        let cols = mats.iter().map(|m| m.cols).sum();
+        let values: Vec<_> = mats.iter().map(|m| m.values()).collect();
+        let mut data = Vec::with_capacity(first.rows * cols);
+        for i in 0..first.rows {
+            for (m, v) in mats.iter().zip(&values) {
+                data.extend_from_slice(&v[i * m.cols..(i + 1) * m.cols]);
+            }
+        }
+        Ok(Matrix::from_parts(data, first.rows, cols).with_dtype(Matrix::stacked_dtype(&mats)))
+    }
+
+    fn matmul(&self, py: Python<'_>, other: &Matrix) -> PyResult<Matrix> {
+        py.allow_threads(|| self.multiply(other))
+    }
+
+    /// `a @ b`; a non-Matrix right operand returns NotImplemented, which
+    /// Python turns into a TypeError
+    fn __matmul__(&self, py: Python<'_>, other: &Matrix) -> PyResult<Matrix> {
+        py.allow_threads(|| self.multiply(other))
+    }
+
+    /// `m ** k` for a square matrix and a non-negative int `k`, by repeated
+    /// squaring; `m ** 0` is the identity
+    fn __pow__(
+        &self,
+        py: Python<'_>,
+        exp: i32,
+        modulo: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Matrix> {
+        if modulo.is_some() {
+            return Err(PyTypeError::new_err(
+                "pow() with a modulus is not supported for Matrix",
+            ));
+        }
+        self.require_square("matrix power")?;
+        if exp < 0 {
+            return Err(PyValueError::new_err(format!(
+                "matrix power needs a non-negative exponent, got {}",
+                exp
+            )));
+        }
+        py.allow_threads(|| {
+            let n = self.rows;
+            let mut result = Matrix::from_parts(vec![0.0; n * n], n, n).with_dtype(self.dtype());
+            result.fill_diagonal(1.0);
+            let mut base = self.clone();
+            let mut exp = exp;
+            while exp > 0 {
+                if exp & 1 == 1 {
+                    result = result.multiply(&base)?;
+                }
+                exp >>= 1;
+                if exp > 0 {
+                    base = base.multiply(&base)?;
+                }
+            }
+            Ok(result)
+        })
+    }
+
+    /// `m + other` with a same-shaped matrix or a scalar
+    fn __add__(&self, other: Operand<'_>) -> PyResult<Matrix> {
+        self.elementwise(other, "add", |a, b| a + b)
+    }
+
+    /// `m - other` with a same-shaped matrix or a scalar
+    fn __sub__(&self, other: Operand<'_>) -> PyResult<Matrix> {
+        self.elementwise(other, "subtract", |a, b| a - b)
+    }
+
+    /// `m * k` scales by a scalar, `m * other` is the elementwise
+    /// (Hadamard) product with a same-shaped matrix; use `@` for the matrix
+    /// product
+    fn __mul__(&self, other: Operand<'_>) -> PyResult<Matrix> {
+        self.elementwise(other, "multiply", |a, b| a * b)
+    }
+
+    /// `k * m`; only reached with a scalar on the left, since a Matrix there
+    /// goes through its own `__mul__`
+    fn __rmul__(&self, k: f64) -> Matrix {
+        self.mapped(|x| x * k)
+    }
+
+    fn __neg__(&self) -> Matrix {
+        self.mapped(|x| -x)
+    }
+
+    /// a new matrix of `func(x)` for every element `x`, in row-major order
+    /// `func` is Python code, so the GIL stays held throughout; if it raises,
+    /// the error says which element it was called on, and a result that
+    /// isn't a number raises TypeError
+    fn map(slf: &Bound<'_, Self>, func: &Bound<'_, PyAny>) -> PyResult<Matrix> {
+        let (rows, cols) = slf.borrow().shape();
+        let data = Matrix::call_elementwise(slf, func)?;
+        Ok(Matrix::from_parts(data, rows, cols).with_dtype(slf.borrow().dtype()))
+    }
+
+    /// like `map`, but writes the results back into this matrix; nothing is
+    /// written unless `func` succeeds on every element
+    /// the borrow is only taken for the write-back, after every call, so
+    /// `func` may read the matrix; if another thread is reading it with the
+    /// GIL released at that point this raises RuntimeError
+    fn map_inplace(slf: &Bound<'_, Self>, func: &Bound<'_, PyAny>) -> PyResult<()> {
+        let shape = slf.borrow().shape();
+        let data = Matrix::call_elementwise(slf, func)?;
+        let mut m = slf.try_borrow_mut()?;
+        if m.shape() != shape {
+            return Err(PyRuntimeError::new_err(
+                "matrix changed shape during map_inplace",
+            ));
+        }
+        for (k, x) in data.into_iter().enumerate() {
+            m.data.set(k, x);
+        }
+        Ok(())
+    }
+
+    fn row(&self, i: usize) -> PyResult<Vec<f64>> {
+        if i >= self.rows {
+            return Err(PyIndexError::new_err(format!(
+                "row {} out of bounds for {}x{} matrix",
+                i, self.rows, self.cols
+            )));
+        }
+        Ok((i * self.cols..(i + 1) * self.cols)
+            .map(|k| self.data.get(k))
+            .collect())
+    }
+
+    fn col(&self, j: usize) -> PyResult<Vec<f64>> {
+        if j >= self.cols {
+            return Err(PyIndexError::new_err(format!(
+                "column {} out of bounds for {}x{} matrix",
+                j, self.rows, self.cols
+            )));
+        }
+        Ok((j..self.data.len())
+            .step_by(self.cols)
+            .map(|k| self.data.get(k))
+            .collect())
+    }
+
+    fn get(&self, i: isize, j: isize) -> PyResult<f64> {
+        Ok(self.data.get(self.offset(i, j)?))
+    }
+
+    fn set(&mut self, i: isize, j: isize, value: f64) -> PyResult<()> {
+        let offset = self.offset(i, j)?;
+        self.data.set(offset, value);
+        Ok(())
+    }
+
+    /// `m[i, j]` is a cell, `m[i, a:b]` / `m[a:b, j]` a row / column
+    /// segment as a list and `m[a:b, c:d]` a sub-matrix; negative indices
+    /// wrap so `m[-1, -1]` is the bottom-right cell, and slices clamp and
+    /// step as they do on lists
+    fn __getitem__(
+        &self,
+        py: Python<'_>,
+        index: (AxisIndex<'_>, AxisIndex<'_>),
+    ) -> PyResult<PyObject> {
+        if let (AxisIndex::Int(i), AxisIndex::Int(j)) = &index {
+            return Ok(self.get(*i, *j)?.into_py(py));
+        }
+        let (rows, cols) = self.select(&index)?;
+        let values: Vec<f64> = rows
+            .iter()
+            .flat_map(|&r| cols.iter().map(move |&c| self.data.get(r * self.cols + c)))
+            .collect();
+        Ok(match index {
+            (AxisIndex::Slice(_), AxisIndex::Slice(_)) => {
+                Matrix::from_parts(values, rows.len(), cols.len())
+                    .with_dtype(self.dtype())
+                    .into_py(py)
+            }
+            _ => values.into_py(py),
+        })
+    }
+
+    /// fills the selection with a scalar, or copies in a matrix or nested
+    /// list of the selection's shape; a single row or column also takes a
+    /// flat list
+    /// takes `slf` unborrowed so the value is converted first, which keeps
+    /// `m[...] = m` from tripping over its own borrow; while another thread
+    /// is reading `m` with the GIL released this raises RuntimeError
+    fn __setitem__(
+        slf: &Bound<'_, Self>,
+        index: (AxisIndex<'_>, AxisIndex<'_>),
+        value: SliceValue,
+    ) -> PyResult<()> {
+        let m = &mut *slf.try_borrow_mut()?;
+        if let ((AxisIndex::Int(i), AxisIndex::Int(j)), SliceValue::Scalar(v)) = (&index, &value) {
+            return m.set(*i, *j, *v);
+        }
+        let (rows, cols) = m.select(&index)?;
+        let shape = (rows.len(), cols.len());
+        let (data, value_shape) = match value {
+            SliceValue::Scalar(v) => (vec![v; shape.0 * shape.1], shape),
+            SliceValue::Matrix(v) => (v.values().into_owned(), v.shape()),
+            SliceValue::Rows(rows) => {
+                let v = Matrix::from_rows(rows)?;
+                (v.values().into_owned(), v.shape())
+            }
+            SliceValue::Flat(v) if shape.0 == 1 => {
+                let len = v.len();
+                (v, (1, len))
+            }
+            SliceValue::Flat(v) => {
+                let len = v.len();
+                (v, (len, 1))
+            }
+        };
+        if value_shape != shape {
+            return Err(mismatch_error("assign", value_shape, "to", shape));
+        }
+        for (k, &r) in rows.iter().enumerate() {
+            for (l, &c) in cols.iter().enumerate() {
+                m.data.set(r * m.cols + c, data[k * shape.1 + l]);
+            }
+        }
+        Ok(())
+    }
+
+    /// a new transposed matrix, or with `in_place=True` transposes this one
+    /// and returns None
+    /// only the in-place form borrows mutably, so `m.transpose()` works
+    /// while another thread is reading `m`, as `m.T` does; the in-place form
+    /// raises RuntimeError then instead
+    #[pyo3(signature = (*, in_place=false))]
+    fn transpose(slf: &Bound<'_, Self>, in_place: bool) -> PyResult<Option<Matrix>> {
+        if !in_place {
+            return Ok(Some(slf.borrow().transposed()));
+        }
+        let mut m = slf.try_borrow_mut()?;
+        if m.exports.load(Ordering::Relaxed) > 0 {
+            return Err(PyBufferError::new_err(
+                "cannot transpose in place while buffer views are exported",
+            ));
+        }
+        *m = m.transposed();
+        Ok(None)
+    }
+
+    /// `(rows, cols)`, as in NumPy
+    #[getter]
+    pub(crate) fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// same buffer in row-major order, reinterpreted as `rows x cols`
+    fn reshape(&self, rows: usize, cols: usize) -> PyResult<Matrix> {
+        if rows.checked_mul(cols) != Some(self.data.len()) {
+            return Err(DimensionMismatchError::with_shapes(
+                format!(
+                    "cannot reshape {}x{} ({} elements) into {}x{}",
+                    self.rows,
+                    self.cols,
+                    self.data.len(),
+                    rows,
+                    cols
+                ),
+                &[self.rows, self.cols],
+                &[rows, cols],
+            ));
+        }
+        Ok(Matrix::from_parts(self.data.clone(), rows, cols))
+    }
+
+    /// sum of the diagonal; square matrices only
+    fn trace(&self) -> PyResult<f64> {
+        self.require_square("trace")?;
+        // fold from +0.0: `sum()` of nothing is -0.0
+        Ok((0..self.rows).fold(0.0, |acc, i| acc + self.data.get(i * self.cols + i)))
+    }
+
+    /// Frobenius norm by default; `ord=1` gives the largest absolute column
+    /// sum and `ord=math.inf` the largest absolute row sum
+    #[pyo3(signature = (ord=None))]
+    fn norm(&self, ord: Option<NormOrd>) -> PyResult<f64> {
+        let max = |sums: Vec<f64>| sums.into_iter().fold(0.0, f64::max);
+        let abs_at = |i: usize, j: usize| self.data.get(i * self.cols + j).abs();
+        match ord {
+            None => Ok(self.data.iter().map(|x| x * x).sum::<f64>().sqrt()),
+            Some(NormOrd::Name(name)) if name == "fro" => self.norm(None),
+            Some(NormOrd::Number(1.0)) => Ok(max((0..self.cols)
+                .map(|j| (0..self.rows).map(|i| abs_at(i, j)).sum())
+                .collect())),
+            Some(NormOrd::Number(f64::INFINITY)) => Ok(max((0..self.rows)
+                .map(|i| (0..self.cols).map(|j| abs_at(i, j)).sum())
+                .collect())),
+            Some(NormOrd::Name(name)) => Err(PyValueError::new_err(format!(
+                "unsupported norm order {:?}, expected 'fro', 1 or inf",
+                name
+            ))),
+            Some(NormOrd::Number(n)) => Err(PyValueError::new_err(format!(
+                "unsupported norm order {:?}, expected 'fro', 1 or inf",
+                n
+            ))),
+        }
+    }
+
+    /// computed from an LU decomposition with partial pivoting, with the
+    /// GIL released
+    fn determinant(&self, py: Python<'_>) -> PyResult<f64> {
+        self.require_square("determinant")?;
+        Ok(py.allow_threads(|| Lu::new(self).determinant()))
+    }
+
+    /// short for `determinant`, as in `numpy.linalg.det`
+    fn det(&self, py: Python<'_>) -> PyResult<f64> {
+        self.determinant(py)
+    }
+
+    /// raises SingularMatrixError when a pivot is numerically zero, i.e. no
+    /// bigger than `tol` times the largest absolute entry
+    #[pyo3(signature = (*, tol=SINGULAR_EPSILON))]
+    fn inverse(&self, py: Python<'_>, tol: f64) -> PyResult<Matrix> {
+        self.require_square("inverse")?;
+        py.allow_threads(|| self.inverted(tol))
+    }
+
+    /// solves `A x = b` by Gaussian elimination with partial pivoting (the
+    /// LU factorization), without forming the inverse; `b` is a `Vector` or
+    /// a list and `x` comes back as the same; `tol` is the singularity
+    /// threshold, as for `inverse`
+    #[pyo3(signature = (b, *, tol=SINGULAR_EPSILON))]
+    fn solve(&self, py: Python<'_>, b: Rhs, tol: f64) -> PyResult<PyObject> {
+        self.require_square("solve")?;
+        let (b, as_vector) = match b {
+            Rhs::Vector(v) => (v.into_vec(), true),
+            Rhs::List(b) => (b, false),
+        };
+        if b.len() != self.rows {
+            return Err(DimensionMismatchError::with_shapes(
+                format!(
+                    "cannot solve {}x{} system with right-hand side of length {}",
+                    self.rows,
+                    self.cols,
+                    b.len()
+                ),
+                &[self.rows, self.cols],
+                &[b.len()],
+            ));
+        }
+        let x = py.allow_threads(|| {
+            let lu = Lu::new(self);
+            if lu.is_singular(tol) {
+                return Err(lu.singular_error());
+            }
+            Ok(lu.solve(&b))
+        })?;
+        Ok(if as_vector {
+            Vector::from_vec(x).into_py(py)
+        } else {
+            x.into_py(py)
+        })
+    }
+
+    /// `m.T`, as in NumPy
+    #[getter(T)]
+    fn t(&self) -> Matrix {
+        self.transposed()
+    }
+
+    fn to_list(&self) -> Vec<Vec<f64>> {
+        self.to_rows()
+    }
+
+    /// `for row in m:` yields each row as a list; the rows are copied when
+    /// iteration starts, so later writes to `m` don't show up
+    fn __iter__(&self) -> MatrixRowIter {
+        MatrixRowIter {
+            rows: self.to_rows().into_iter(),
+        }
+    }
+
+    /// the number of rows, matching iteration
+    fn __len__(&self) -> usize {
+        self.rows
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Matrix({}x{})", self.rows, self.cols)
+    }
+
+    /// same shape and exactly equal elements; by IEEE rules a matrix
+    /// containing NaN is never equal to anything, itself included
+    /// a non-Matrix `other` fails extraction, which pyo3 turns into
+    /// NotImplemented; the dtype is not compared, so a float32 matrix equals
+    /// its float64 copy
+    fn __eq__(&self, other: &Matrix) -> bool {
+        self.shape() == other.shape() && self.data.iter().eq(other.data.iter())
+    }
+
+    /// elementwise `|a - b| <= atol + rtol * |b|`, as `numpy.allclose`;
+    /// infinities only match themselves and NaN matches nothing unless
+    /// `equal_nan` is set; different shapes are simply not close
+    #[pyo3(signature = (other, rtol=1e-5, atol=1e-8, equal_nan=false))]
+    fn allclose(&self, other: &Matrix, rtol: f64, atol: f64, equal_nan: bool) -> bool {
+        self.shape() == other.shape()
+            && self.data.iter().zip(other.data.iter()).all(|(a, b)| {
+                if a.is_nan() || b.is_nan() {
+                    equal_nan && a.is_nan() && b.is_nan()
+                } else if a.is_infinite() || b.is_infinite() {
+                    a == b
+                } else {
+                    (a - b).abs() <= atol + rtol * b.abs()
+                }
+            })
+    }
+
+    /// hashed from the shape and the element bits, so bitwise-identical
+    /// matrices collide; mutating a matrix that is already a dict key or set
+    /// member changes its hash, as with any mutable Python object
+    fn __hash__(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.shape().hash(&mut hasher);
+        for x in self.data.iter() {
+            // 0.0 == -0.0, so they must hash alike
+            let x = if x == 0.0 { 0.0 } else { x };
+            x.to_bits().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// pickle support
+    /// unpickling starts from `Matrix([])` and then restores the
+    /// `(rows, cols, data, dtype)` state, which also covers 0xN shapes that a
+    /// list of rows can't express; states pickled before dtypes existed
+    /// have no fourth item and load as float64
+    fn __getnewargs__(&self) -> (Vec<Vec<f64>>,) {
+        (Vec::new(),)
+    }
+
+    fn __getstate__(&self) -> (usize, usize, Vec<f64>, &'static str) {
+        (self.rows, self.cols, self.values().into_owned(), self.dtype().name())
+    }
+
+    fn __setstate__(&mut self, state: &Bound<'_, PyAny>) -> PyResult<()> {
+        const EXPECTED: &str = "a (rows, cols, data, dtype) tuple with a flat list of floats";
+        let (rows, cols, data, dtype) = match state.extract::<(usize, usize, Vec<f64>)>() {
+            Ok((rows, cols, data)) => (rows, cols, data, DType::F64),
+            Err(_) => {
+                let (rows, cols, data, dtype): (usize, usize, Vec<f64>, String) =
+                    crate::extract_state(state, "Matrix", EXPECTED)?;
+                let dtype = DType::from_name(&dtype).ok_or_else(|| {
+                    PyValueError::new_err(format!(
+                        "invalid pickle state for Matrix: unknown dtype {:?}",
+                        dtype
+                    ))
+                })?;
+                (rows, cols, data, dtype)
+            }
+        };
+        if rows.checked_mul(cols) != Some(data.len()) {
+            return Err(PyValueError::new_err(format!(
+                "invalid pickle state for Matrix: {}x{} needs {} elements, got {}",
+                rows,
+                cols,
+                rows.saturating_mul(cols),
+                data.len()
+            )));
+        }
+        if self.exports.load(Ordering::Relaxed) > 0 {
+            return Err(PyBufferError::new_err(
+                "cannot restore state while buffer views are exported",
+            ));
+        }
+        (self.data, self.rows, self.cols) = (Storage::F64(data).cast(dtype), rows, cols);
+        Ok(())
+    }
+
+    /// copy support
+    /// both clone the element buffer directly, skipping the pickle round
+    /// trip through a list; the elements are plain floats, so shallow and
+    /// deep copies are the same, and neither shares buffer exports
+    fn __copy__(&self) -> Matrix {
+        self.clone()
+    }
+
+    fn __deepcopy__(&self, _memo: &Bound<'_, PyDict>) -> Matrix {
+        self.clone()
+    }
+
+    /// rows aligned in columns, NumPy style; tall matrices keep the first
+    /// and last few rows around an ellipsis
+    fn __str__(&self) -> String {
+        if self.rows == 0 {
+            return "[]".to_string();
+        }
+        let shown: Vec<Option<usize>> = if self.rows > STR_MAX_ROWS {
+            let edge = STR_MAX_ROWS / 2;
+            (0..edge)
+                .map(Some)
+                .chain(std::iter::once(None))
+                .chain((self.rows - edge..self.rows).map(Some))
+                .collect()
+        } else {
+            (0..self.rows).map(Some).collect()
+        };
+
+        let cells: Vec<Option<Vec<String>>> = shown
+            .iter()
+            .map(|row| {
+                row.map(|i| {
+                    let range = i * self.cols..(i + 1) * self.cols;
+                    // float32 prints its own shortest repr, not the widened one
+                    match &self.data {
+                        Storage::F32(data) => data[range].iter().map(|x| format!("{:?}", x)).collect(),
+                        Storage::F64(data) => data[range].iter().map(|x| format!("{:?}", x)).collect(),
+                    }
+                })
+            })
+            .collect();
+        let width = cells.iter().flatten().flatten().map(String::len).max();
+
+        let lines: Vec<String> = cells
+            .iter()
+            .map(|row| match row {
+                Some(row) => {
+                    let padded: Vec<String> = row
+                        .iter()
+                        .map(|cell| format!("{:>1$}", cell, width.unwrap_or(0)))
+                        .collect();
+                    format!("[{}]", padded.join(" "))
+                }
+                None => "...".to_string(),
+            })
+            .collect();
+        format!("[{}]", lines.join("\n "))
+    }
+
+    /// buffer protocol
+    /// exposes `data` as a read-only 2-D C-contiguous buffer of float64 or
+    /// float32 (format "d" or "f"), so `memoryview(m)` and `np.asarray(m)`
+    /// share memory with the matrix
+    /// read-only because `matmul`, `inverse` and friends read `data` with
+    /// the GIL released, where writes through a view would be a data race;
+    /// writes go through `m[i, j] = x`, which views then see
+    unsafe fn __getbuffer__(
+        slf: PyRef<'_, Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        if view.is_null() {
+            return Err(PyBufferError::new_err("view is null"));
+        }
+        if (flags & ffi::PyBUF_WRITABLE) == ffi::PyBUF_WRITABLE {
+            return Err(PyBufferError::new_err(
+                "Matrix buffers are read-only; assign through m[i, j] instead",
+            ));
+        }
+        if (flags & ffi::PyBUF_F_CONTIGUOUS) == ffi::PyBUF_F_CONTIGUOUS
+            && slf.rows > 1
+            && slf.cols > 1
+        {
+            return Err(PyBufferError::new_err("matrix is not Fortran contiguous"));
+        }
+
+        let (buf, item, format): (*const c_void, usize, &CStr) = match &slf.data {
+            Storage::F32(data) => (data.as_ptr().cast(), std::mem::size_of::<f32>(), c"f"),
+            Storage::F64(data) => (data.as_ptr().cast(), std::mem::size_of::<f64>(), c"d"),
+        };
+        let item = item as ffi::Py_ssize_t;
+        let (rows, cols) = (slf.rows as ffi::Py_ssize_t, slf.cols as ffi::Py_ssize_t);
+        // shape followed by strides, freed in __releasebuffer__
+        let dims = Box::into_raw(Box::new([rows, cols, cols * item, item]));
+
+        (*view).buf = buf.cast_mut();
+        (*view).len = rows * cols * item;
+        (*view).itemsize = item;
+        (*view).readonly = 1;
+        (*view).ndim = 2;
+        (*view).format = if (flags & ffi::PyBUF_FORMAT) == ffi::PyBUF_FORMAT {
+            format.as_ptr() as *mut c_char
+        } else {
+            std::ptr::null_mut()
+        };
+        (*view).shape = if (flags & ffi::PyBUF_ND) == ffi::PyBUF_ND {
+            dims.cast()
+        } else {
+            std::ptr::null_mut()
+        };
+        (*view).strides = if (flags & ffi::PyBUF_STRIDES) == ffi::PyBUF_STRIDES {
+            dims.cast::<ffi::Py_ssize_t>().add(2)
+        } else {
+            std::ptr::null_mut()
+        };
+        (*view).suboffsets = std::ptr::null_mut();
+        (*view).internal = dims.cast();
+
+        slf.exports.fetch_add(1, Ordering::Relaxed);
+        (*view).obj = slf.into_ptr();
+        Ok(())
+    }
+
+    unsafe fn __releasebuffer__(slf: PyRef<'_, Self>, view: *mut ffi::Py_buffer) {
+        drop(Box::from_raw(
+            (*view).internal.cast::<[ffi::Py_ssize_t; 4]>(),
+        ));
+        slf.exports.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// a read-only ndarray of the same dtype sharing memory with the matrix,
+    /// made by `np.asarray` through the buffer protocol, so no copy: while
+    /// it lives the matrix can't be transposed in place, and `m[i, j] = x`
+    /// shows through it; `.copy()` it for a writable array of its own
+    fn to_numpy<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        PyModule::import_bound(slf.py(), "numpy")?.call_method1("asarray", (slf,))
+    }
+}
+
+/// iterator returned by `Matrix.__iter__`
+#[pyclass]
+struct MatrixRowIter {
+    rows: std::vec::IntoIter<Vec<f64>>,
+}
+
+#[pymethods]
+impl MatrixRowIter {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> Option<Vec<f64>> {
+        self.rows.next()
+    }
+}
+
+/// which matmul implementation this build was compiled with
+#[pyfunction]
+pub fn backend() -> &'static str {
+    if cfg!(feature = "blas") {
+        "blas"
+    } else {
+        "native"
+    }
+}
+
+/// a 2-D numpy array of one of the dtypes `Matrix` can store
+#[derive(FromPyObject)]
+pub enum NumpyArray<'py> {
+    F64(PyReadonlyArray2<'py, f64>),
+    F32(PyReadonlyArray2<'py, f32>),
+}
+
+/// copies `view` into a `Matrix`: C-contiguous arrays in one go, anything
+/// else (Fortran order, strided slices) element by element in row-major order
+fn matrix_from_view<T>(py: Python<'_>, view: ArrayView2<'_, T>) -> Matrix
+where
+    T: Copy + Send + Sync,
+    Vec<T>: Into<Storage>,
+{
+    let (rows, cols) = view.dim();
+    let data = py.allow_threads(|| match view.as_slice() {
+        Some(slice) => slice.to_vec(),
+        None => view.iter().copied().collect(),
+    });
+    Matrix::from_parts(data, rows, cols)
+}
+
+/// builds a `Matrix` from a 2-D float32 or float64 array, keeping its dtype
+#[pyfunction]
+pub fn from_numpy(py: Python<'_>, arr: NumpyArray<'_>) -> Matrix {
+    match arr {
+        NumpyArray::F64(arr) => matrix_from_view(py, arr.as_array()),
+        NumpyArray::F32(arr) => matrix_from_view(py, arr.as_array()),
+    }
+}
+
+/// multiplies two 2-D float64 arrays straight from their numpy buffers
+/// strided and Fortran-order inputs are read in place without a copy; other
+/// dtypes are rejected with a TypeError during argument conversion, so cast
+/// with `arr.astype(np.float64)` first
+#[pyfunction]
+pub fn matmul_np<'py>(
+    py: Python<'py>,
+    a: PyReadonlyArray2<'py, f64>,
+    b: PyReadonlyArray2<'py, f64>,
+) -> PyResult<Bound<'py, PyArray2<f64>>> {
+    let (a, b) = (a.as_array(), b.as_array());
+    if a.ncols() != b.nrows() {
+        return Err(mismatch_error("multiply", a.dim(), "by", b.dim()));
+    }
+    // the readonly borrows keep both buffers alive and unaliased while the
+    // GIL is dropped, so only the plain views cross into the closure
+    let product = py.allow_threads(|| a.dot(&b));
+    Ok(product.into_pyarray_bound(py))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_py<T>(f: impl FnOnce(Python<'_>) -> T) -> T {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(f)
+    }
+
+    fn matrix(rows: &[&[f64]]) -> Matrix {
+        Matrix::from_rows(rows.iter().map(|row| row.to_vec()).collect()).unwrap()
+    }
+
+    fn random_matrix(rows: usize, cols: usize, seed: u64) -> Matrix {
+        let mut m = Matrix::from_parts(vec![0.0; rows * cols], rows, cols);
+        m.fill_with(Some(seed), |rng| rng.uniform(-1.0, 1.0));
+        m
+    }
+
+    fn assert_inverse(rows: &[&[f64]]) {
+        with_py(|py| {
+            let m = matrix(rows);
+            let product = m.multiply(&m.inverse(py, SINGULAR_EPSILON).unwrap()).unwrap();
+            let n = rows.len();
+            for (k, x) in product.values().iter().enumerate() {
+                let expected = if k / n == k % n { 1.0 } else { 0.0 };
+                assert!((x - expected).abs() < 1e-12, "{:?}", product.to_rows());
+            }
+        })
+    }
+
+    #[test]
+    fn inverse_2x2_times_original_is_identity() {
+        assert_inverse(&[&[4.0, 7.0], &[2.0, 6.0]]);
+    }
+
+    #[test]
+    fn inverse_3x3_times_original_is_identity() {
+        assert_inverse(&[&[2.0, 0.0, 1.0], &[1.0, 3.0, 2.0], &[1.0, 1.0, 2.0]]);
+    }
+
+    #[test]
+    fn inverse_3x3_known_values() {
+        with_py(|py| {
+            let inverse = matrix(&[&[2.0, 0.0, 1.0], &[1.0, 3.0, 2.0], &[1.0, 1.0, 2.0]])
+                .inverse(py, SINGULAR_EPSILON)
+                .unwrap();
+            let expected = [4.0, 1.0, -3.0, 0.0, 3.0, -3.0, -2.0, -2.0, 6.0].map(|x| x / 6.0);
+            for (x, e) in inverse.values().iter().zip(expected) {
+                assert!((x - e).abs() < 1e-12);
+            }
+        })
+    }
+
+    #[test]
+    fn inverse_of_singular_raises_singular_matrix_error() {
+        with_py(|py| {
+            let err = matrix(&[&[1.0, 2.0], &[2.0, 4.0]])
+                .inverse(py, SINGULAR_EPSILON)
+                .err()
+                .unwrap();
+            assert!(err.is_instance_of::<SingularMatrixError>(py));
+        })
+    }
+
+    #[test]
+    fn inverse_of_non_square_raises_dimension_mismatch_error() {
+        with_py(|py| {
+            let err = matrix(&[&[1.0, 2.0, 3.0]])
+                .inverse(py, SINGULAR_EPSILON)
+                .err()
+                .unwrap();
+            assert!(err.is_instance_of::<DimensionMismatchError>(py));
+        })
+    }
+
+    #[cfg(feature = "blas")]
+    #[test]
+    fn blas_matches_native_256x256() {
+        let (a, b) = (random_matrix(256, 256, 1), random_matrix(256, 256, 2));
+        let blas = a.multiply(&b).unwrap();
+        let mut native = vec![0.0; 256 * 256];
+        a.multiply_native(&b, &mut native, false);
+        for (x, y) in blas.values().iter().zip(&native) {
+            assert!((x - y).abs() < 1e-10, "{} != {}", x, y);
+        }
+    }
+
+    #[test]
+    fn parallel_matches_serial() {
+        let (a, b) = (random_matrix(300, 200, 3), random_matrix(200, 250, 4));
+        let parallel = a.multiply_parallel(&b).unwrap();
+        let mut serial = vec![0.0; 300 * 250];
+        a.multiply_native(&b, &mut serial, false);
+        assert_eq!(parallel.shape(), (300, 250));
+        assert_eq!(parallel.values().as_ref(), serial.as_slice());
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{Mutex, PoisonError};
+
+use pyo3::prelude::*;
+
+/// generator shared by every `seed=None` call once `set_default_seed` has
+/// been called
+static DEFAULT_RNG: Mutex<Option<Pcg32>> = Mutex::new(None);
+
+/// PCG-XSH-RR with 64 bits of state and 32-bit output (O'Neill's `pcg32`),
+/// so a seed gives the same stream on every platform and build
+pub struct Pcg32 {
+    state: u64,
+}
+
+impl Pcg32 {
+    const MULTIPLIER: u64 = 6364136223846793005;
+    const INCREMENT: u64 = 1442695040888963407;
+
+    /// seeded as the reference `pcg32_srandom_r`
+    pub fn new(seed: u64) -> Self {
+        let mut rng = Pcg32 { state: 0 };
+        rng.next_u32();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.next_u32();
+        rng
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old
+            .wrapping_mul(Self::MULTIPLIER)
+            .wrapping_add(Self::INCREMENT);
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        xorshifted.rotate_right((old >> 59) as u32)
+    }
+
+    /// uniform in `[0, 1)` with 53 random bits
+    fn next_f64(&mut self) -> f64 {
+        let bits = ((self.next_u32() as u64) << 32 | self.next_u32() as u64) >> 11;
+        bits as f64 / (1u64 << 53) as f64
+    }
+
+    /// uniform in `[low, high)`
+    pub fn uniform(&mut self, low: f64, high: f64) -> f64 {
+        let x = low + (high - low) * self.next_f64();
+        // the product can round up to `high` itself
+        if x < high {
+            x
+        } else {
+            high.next_down()
+        }
+    }
+
+    /// standard normal sample by Marsaglia's polar method
+    pub fn standard_normal(&mut self) -> f64 {
+        loop {
+            let u = 2.0 * self.next_f64() - 1.0;
+            let v = 2.0 * self.next_f64() - 1.0;
+            let s = u * u + v * v;
+            if s > 0.0 && s < 1.0 {
+                return u * (-2.0 * s.ln() / s).sqrt();
+            }
+        }
+    }
+}
+
+/// a seed from the OS-randomized keys std already uses for `HashMap`
+fn entropy() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+/// runs `f` with the generator for a `seed` argument: a fresh one for an
+/// explicit seed, otherwise the default one from `set_default_seed`, or a
+/// randomly seeded one if there is none
+pub fn with_rng<T>(seed: Option<u64>, f: impl FnOnce(&mut Pcg32) -> T) -> T {
+    if let Some(seed) = seed {
+        return f(&mut Pcg32::new(seed));
+    }
+    let mut default = DEFAULT_RNG.lock().unwrap_or_else(PoisonError::into_inner);
+    match default.as_mut() {
+        Some(rng) => f(rng),
+        None => f(&mut Pcg32::new(entropy())),
+    }
+}
+
+/// makes `Matrix.random` / `Matrix.randn` calls without a seed draw from
+/// one generator seeded with `seed`, so a whole sequence of them is
+/// reproducible; `None` goes back to unpredictable seeds
+#[pyfunction]
+#[pyo3(signature = (seed))]
+pub fn set_default_seed(seed: Option<u64>) {
+    *DEFAULT_RNG.lock().unwrap_or_else(PoisonError::into_inner) = seed.map(Pcg32::new);
+}
@@ -0,0 +1,220 @@
+use pyo3::{exceptions::PyIndexError, prelude::*, types::PyDict};
+
+use crate::matrix::{mismatch_error, Matrix};
+
+/// sparse matrix in compressed sparse row (CSR) layout: row `i` keeps its
+/// column indices, sorted, in `indices[indptr[i]..indptr[i + 1]]` and the
+/// matching values in the same range of `data`
+#[pyclass(module = "matrix_mul")]
+#[derive(Clone)]
+pub struct SparseMatrix {
+    #[pyo3(get)]
+    data: Vec<f64>,
+    #[pyo3(get)]
+    indices: Vec<usize>,
+    #[pyo3(get)]
+    indptr: Vec<usize>,
+    rows: usize,
+    cols: usize,
+}
+
+/// right-hand side of `SparseMatrix @ other`
+#[derive(FromPyObject)]
+enum MatmulOperand<'py> {
+    Sparse(PyRef<'py, SparseMatrix>),
+    Dense(PyRef<'py, Matrix>),
+}
+
+impl SparseMatrix {
+    /// builds the CSR arrays from `(row, col, value)` triplets in any order,
+    /// summing duplicates; every index must already be in bounds
+    fn from_triplets(mut triplets: Vec<(usize, usize, f64)>, rows: usize, cols: usize) -> Self {
+        triplets.sort_by_key(|&(i, j, _)| (i, j));
+        let mut data: Vec<f64> = Vec::with_capacity(triplets.len());
+        let mut indices = Vec::with_capacity(triplets.len());
+        let mut indptr = vec![0; rows + 1];
+        let mut last = None;
+        for (i, j, v) in triplets {
+            match data.last_mut() {
+                Some(sum) if last == Some((i, j)) => *sum += v,
+                _ => {
+                    data.push(v);
+                    indices.push(j);
+                    indptr[i + 1] += 1;
+                    last = Some((i, j));
+                }
+            }
+        }
+        for i in 0..rows {
+            indptr[i + 1] += indptr[i];
+        }
+        SparseMatrix {
+            data,
+            indices,
+            indptr,
+            rows,
+            cols,
+        }
+    }
+
+    /// `(column, value)` pairs of the stored entries in row `i`
+    fn row_entries(&self, i: usize) -> impl Iterator<Item = (usize, f64)> + '_ {
+        let range = self.indptr[i]..self.indptr[i + 1];
+        self.indices[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.data[range].iter().copied())
+    }
+
+    fn check_matmul(&self, rhs: (usize, usize)) -> PyResult<()> {
+        if self.cols != rhs.0 {
+            return Err(mismatch_error("multiply", self.shape(), "by", rhs));
+        }
+        Ok(())
+    }
+
+    fn multiply_dense(&self, other: &Matrix) -> Matrix {
+        let n = other.shape().1;
+        let b = other.values();
+        let mut out = vec![0.0; self.rows * n];
+        for i in 0..self.rows {
+            for (k, a) in self.row_entries(i) {
+                for j in 0..n {
+                    out[i * n + j] += a * b[k * n + j];
+                }
+            }
+        }
+        Matrix::from_parts(out, self.rows, n)
+    }
+
+    /// Gustavson's row-by-row product; entries that cancel to exactly zero
+    /// are left out of the result
+    fn multiply_sparse(&self, other: &SparseMatrix) -> SparseMatrix {
+        let mut acc = vec![0.0; other.cols];
+        let mut touched = vec![false; other.cols];
+        let mut row_cols = Vec::new();
+        let (mut data, mut indices, mut indptr) = (Vec::new(), Vec::new(), vec![0]);
+        for i in 0..self.rows {
+            for (k, a) in self.row_entries(i) {
+                for (j, b) in other.row_entries(k) {
+                    if !touched[j] {
+                        touched[j] = true;
+                        row_cols.push(j);
+                    }
+                    acc[j] += a * b;
+                }
+            }
+            row_cols.sort_unstable();
+            for j in row_cols.drain(..) {
+                if acc[j] != 0.0 {
+                    data.push(acc[j]);
+                    indices.push(j);
+                }
+                acc[j] = 0.0;
+                touched[j] = false;
+            }
+            indptr.push(data.len());
+        }
+        SparseMatrix {
+            data,
+            indices,
+            indptr,
+            rows: self.rows,
+            cols: other.cols,
+        }
+    }
+}
+
+#[pymethods]
+impl SparseMatrix {
+    /// from a `{(i, j): value}` dict, or any iterable of `((i, j), value)`
+    /// pairs, where repeated coordinates are summed; `shape` defaults to
+    /// just big enough for the largest indices
+    #[new]
+    #[pyo3(signature = (entries, shape=None))]
+    fn new(entries: &Bound<'_, PyAny>, shape: Option<(usize, usize)>) -> PyResult<Self> {
+        let entries = match entries.downcast::<PyDict>() {
+            Ok(dict) => dict.items().into_any(),
+            Err(_) => entries.clone(),
+        };
+        let mut triplets = Vec::new();
+        for entry in entries.iter()? {
+            let ((i, j), v): ((usize, usize), f64) = entry?.extract()?;
+            triplets.push((i, j, v));
+        }
+
+        let (rows, cols) = shape.unwrap_or_else(|| {
+            triplets
+                .iter()
+                .fold((0, 0), |(r, c), &(i, j, _)| (r.max(i + 1), c.max(j + 1)))
+        });
+        if let Some(&(i, j, _)) = triplets.iter().find(|&&(i, j, _)| i >= rows || j >= cols) {
+            return Err(PyIndexError::new_err(format!(
+                "entry ({}, {}) out of bounds for {}x{} sparse matrix",
+                i, j, rows, cols
+            )));
+        }
+        Ok(SparseMatrix::from_triplets(triplets, rows, cols))
+    }
+
+    /// keeps the entries of `m` whose magnitude is above `tol`
+    #[staticmethod]
+    #[pyo3(signature = (m, tol=0.0))]
+    fn from_dense(m: &Matrix, tol: f64) -> SparseMatrix {
+        let (rows, cols) = m.shape();
+        let values = m.values();
+        let triplets = (0..rows)
+            .flat_map(|i| (0..cols).map(move |j| (i, j)))
+            .map(|(i, j)| (i, j, values[i * cols + j]))
+            .filter(|&(_, _, x)| x.is_nan() || x.abs() > tol)
+            .collect();
+        SparseMatrix::from_triplets(triplets, rows, cols)
+    }
+
+    /// number of stored entries
+    #[getter]
+    fn nnz(&self) -> usize {
+        self.data.len()
+    }
+
+    /// `(rows, cols)`, as for `Matrix`
+    #[getter]
+    fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    fn to_dense(&self) -> Matrix {
+        let mut data = vec![0.0; self.rows * self.cols];
+        for i in 0..self.rows {
+            for (j, x) in self.row_entries(i) {
+                data[i * self.cols + j] = x;
+            }
+        }
+        Matrix::from_parts(data, self.rows, self.cols)
+    }
+
+    /// a sparse right-hand side gives a `SparseMatrix`, a dense one a
+    /// float64 `Matrix`
+    fn matmul(&self, py: Python<'_>, other: MatmulOperand<'_>) -> PyResult<PyObject> {
+        match other {
+            MatmulOperand::Sparse(other) => {
+                self.check_matmul(other.shape())?;
+                let other: &SparseMatrix = &other;
+                Ok(py.allow_threads(|| self.multiply_sparse(other)).into_py(py))
+            }
+            MatmulOperand::Dense(other) => {
+                self.check_matmul(other.shape())?;
+                let other: &Matrix = &other;
+                Ok(py.allow_threads(|| self.multiply_dense(other)).into_py(py))
+            }
+        }
+    }
+
+    fn __matmul__(&self, py: Python<'_>, other: MatmulOperand<'_>) -> PyResult<PyObject> {
+        self.matmul(py, other)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("SparseMatrix({}x{}, nnz={})", self.rows, self.cols, self.nnz())
+    }
+}
@@ -0,0 +1,137 @@
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::DimensionMismatchError;
+
+/// dense vector of floats
+#[pyclass(module = "matrix_mul")]
+#[derive(Clone)]
+pub struct Vector {
+    #[pyo3(get)]
+    components: Vec<f64>,
+}
+
+impl Vector {
+    pub fn from_vec(components: Vec<f64>) -> Self {
+        Vector { components }
+    }
+
+    pub fn into_vec(self) -> Vec<f64> {
+        self.components
+    }
+
+    fn check_len(&self, other: &Vector, verb: &str) -> PyResult<()> {
+        if self.components.len() != other.components.len() {
+            return Err(DimensionMismatchError::with_shapes(
+                format!(
+                    "cannot {} vectors of length {} and {}",
+                    verb,
+                    self.components.len(),
+                    other.components.len()
+                ),
+                &[self.components.len()],
+                &[other.components.len()],
+            ));
+        }
+        Ok(())
+    }
+
+    fn zip_with(
+        &self,
+        other: &Vector,
+        verb: &str,
+        f: impl Fn(f64, f64) -> f64,
+    ) -> PyResult<Vector> {
+        self.check_len(other, verb)?;
+        Ok(Vector::from_vec(
+            self.components
+                .iter()
+                .zip(&other.components)
+                .map(|(&a, &b)| f(a, b))
+                .collect(),
+        ))
+    }
+}
+
+#[pymethods]
+impl Vector {
+    #[new]
+    fn new(components: Vec<f64>) -> Self {
+        Vector::from_vec(components)
+    }
+
+    fn dot(&self, other: &Vector) -> PyResult<f64> {
+        self.check_len(other, "take the dot product of")?;
+        Ok(self
+            .components
+            .iter()
+            .zip(&other.components)
+            .map(|(a, b)| a * b)
+            .sum())
+    }
+
+    /// only defined for 3-D vectors
+    fn cross(&self, other: &Vector) -> PyResult<Vector> {
+        match (self.components.as_slice(), other.components.as_slice()) {
+            (&[a1, a2, a3], &[b1, b2, b3]) => Ok(Vector::from_vec(vec![
+                a2 * b3 - a3 * b2,
+                a3 * b1 - a1 * b3,
+                a1 * b2 - a2 * b1,
+            ])),
+            _ => Err(PyValueError::new_err(format!(
+                "cross product needs two 3-D vectors, got lengths {} and {}",
+                self.components.len(),
+                other.components.len()
+            ))),
+        }
+    }
+
+    fn magnitude(&self) -> f64 {
+        self.components.iter().map(|x| x * x).sum::<f64>().sqrt()
+    }
+
+    fn normalize(&self) -> PyResult<Vector> {
+        let magnitude = self.magnitude();
+        if magnitude == 0.0 {
+            return Err(PyValueError::new_err("cannot normalize a zero vector"));
+        }
+        Ok(Vector::from_vec(
+            self.components.iter().map(|x| x / magnitude).collect(),
+        ))
+    }
+
+    #[pyo3(name = "__add__")]
+    fn add(&self, other: &Vector) -> PyResult<Vector> {
+        self.zip_with(other, "add", |a, b| a + b)
+    }
+
+    #[pyo3(name = "__sub__")]
+    fn sub(&self, other: &Vector) -> PyResult<Vector> {
+        self.zip_with(other, "subtract", |a, b| a - b)
+    }
+
+    /// scalar multiplication
+    #[pyo3(name = "__mul__")]
+    fn mul(&self, k: f64) -> Vector {
+        Vector::from_vec(self.components.iter().map(|x| x * k).collect())
+    }
+
+    #[pyo3(name = "__rmul__")]
+    fn rmul(&self, k: f64) -> Vector {
+        self.mul(k)
+    }
+
+    fn __len__(&self) -> usize {
+        self.components.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Vector({:?})", self.components)
+    }
+}
+
+/// dot product of two plain sequences, for when building `Vector`s or 1xN
+/// matrices would be overkill
+#[pyfunction]
+pub fn dot(a: Vec<f64>, b: Vec<f64>) -> PyResult<f64> {
+    Vector::from_vec(a).dot(&Vector::from_vec(b))
+}
@@ -0,0 +1,43 @@
+import pytest
+
+from matrix_mul import DimensionMismatchError, Matrix
+
+
+@pytest.mark.parametrize("k", [2, 2.5, -1, 0])
+def test_scalar_multiplication_commutes(k):
+    m = Matrix([[1, 2], [3, 4]])
+    assert k * m == m * k
+    assert (m * k).to_list() == [[k * 1.0, k * 2.0], [k * 3.0, k * 4.0]]
+
+
+def test_add_sub_neg():
+    a = Matrix([[1, 2], [3, 4]])
+    b = Matrix([[4, 3], [2, 1]])
+    assert (a + b).to_list() == [[5.0, 5.0], [5.0, 5.0]]
+    assert (a - b).to_list() == [[-3.0, -1.0], [1.0, 3.0]]
+    assert -a == a * -1
+    with pytest.raises(DimensionMismatchError):
+        a + Matrix([[1, 2, 3]])
+
+
+@pytest.mark.parametrize(
+    "op",
+    [
+        lambda m: m * "x",
+        lambda m: "x" * m,
+        lambda m: m + "x",
+        lambda m: "x" + m,
+        lambda m: m - "x",
+        lambda m: m @ "x",
+    ],
+)
+def test_unsupported_operands_raise_type_error(op):
+    with pytest.raises(TypeError):
+        op(Matrix([[1, 2], [3, 4]]))
+
+
+def test_unsupported_operands_return_not_implemented():
+    m = Matrix([[1, 2], [3, 4]])
+    assert m.__mul__("x") is NotImplemented
+    assert m.__rmul__("x") is NotImplemented
+    assert m.__add__("x") is NotImplemented
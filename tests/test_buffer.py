@@ -0,0 +1,72 @@
+import ctypes
+import threading
+
+import pytest
+
+from matrix_mul import DType, Matrix
+
+
+def test_memoryview_shape_strides_and_format():
+    view = memoryview(Matrix([[1, 2, 3], [4, 5, 6]]))
+    assert view.shape == (2, 3)
+    assert view.strides == (24, 8)
+    assert view.format == "d"
+    assert view.itemsize == 8
+    assert view.c_contiguous
+    assert view.tolist() == [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]
+
+
+def test_float32_buffer():
+    view = memoryview(Matrix([[1, 2]], dtype=DType.F32))
+    assert view.format == "f"
+    assert view.itemsize == 4
+    assert view.nbytes == 8
+
+
+def test_buffer_is_read_only_but_shares_memory():
+    m = Matrix([[1, 2], [3, 4]])
+    view = memoryview(m)
+    assert view.readonly
+    with pytest.raises(TypeError):
+        view[0, 0] = 9.0
+    m[0, 0] = 9.0
+    assert view[0, 0] == 9.0
+
+
+def test_writable_request_is_refused():
+    # ctypes asks for a writable buffer
+    with pytest.raises((BufferError, TypeError)):
+        (ctypes.c_double * 4).from_buffer(Matrix([[1, 2], [3, 4]]))
+
+
+def test_reallocation_blocked_while_exported():
+    m = Matrix([[1, 2, 3]])
+    view = memoryview(m)
+    with pytest.raises(BufferError):
+        m.transpose(in_place=True)
+    view.release()
+    m.transpose(in_place=True)
+    assert m.shape == (3, 1)
+
+
+def test_views_released_while_another_thread_computes():
+    m = Matrix.identity(120) * 2 + Matrix.ones(120, 120)
+    stop = threading.Event()
+
+    def invert():
+        while not stop.is_set():
+            m.inverse()
+            m.det()
+
+    worker = threading.Thread(target=invert)
+    worker.start()
+    try:
+        for _ in range(200):
+            view = memoryview(m)
+            assert view[0, 0] == 3.0
+            view.release()
+    finally:
+        stop.set()
+        worker.join()
+    # every export was released, so the matrix can be reallocated again
+    m.transpose(in_place=True)
@@ -0,0 +1,134 @@
+import copy
+
+import pytest
+
+import matrix_mul
+from matrix_mul import BaseClass, ClassOne, SubClassA, TupleClas
+
+
+def test_from_base_id_matches_constructor():
+    m = SubClassA.from_base_id(5)
+    assert type(m) is SubClassA
+    assert m.full_id() == SubClassA(5).full_id() == "base=5,sub=170"
+
+
+def test_from_base_id_builds_python_subclasses():
+    class Tagged(SubClassA):
+        def __init__(self, id):
+            self.tag = f"tagged {id}"
+
+    m = Tagged.from_base_id(2)
+    assert type(m) is Tagged
+    assert m.tag == "tagged 2"
+    assert m.full_id() == "base=2,sub=68"
+
+
+def test_id2_overflow():
+    with pytest.raises(OverflowError):
+        SubClassA(2**30)
+    with pytest.raises(OverflowError):
+        SubClassA.from_base_id(-(2**30))
+
+
+def test_class_one_id_is_assignable_and_hashable():
+    c = ClassOne(5)
+    c.id = 7
+    assert c.id == 7
+    assert c == ClassOne(7)
+    assert hash(c) == hash(ClassOne(7))
+    assert len({ClassOne(1), ClassOne(1), ClassOne(2)}) == 2
+    assert {ClassOne(3): "three"}[ClassOne(3)] == "three"
+
+
+def test_class_one_arithmetic():
+    assert ClassOne(2) + ClassOne(3) == ClassOne(5)
+    assert ClassOne(2) - ClassOne(3) == ClassOne(-1)
+    assert -ClassOne(4) == ClassOne(-4)
+    assert abs(ClassOne(-4)) == ClassOne(4)
+    assert int(ClassOne(7)) == 7
+    assert float(ClassOne(7)) == 7.0
+
+
+def test_class_one_comparisons():
+    assert ClassOne(1) < ClassOne(2) <= ClassOne(2)
+    assert ClassOne(3) > ClassOne(2) >= ClassOne(2)
+    assert ClassOne(1) != ClassOne(2)
+    assert sorted([ClassOne(3), ClassOne(1), ClassOne(2)]) == [ClassOne(1), ClassOne(2), ClassOne(3)]
+    assert ClassOne(1) != 1
+    with pytest.raises(TypeError):
+        ClassOne(1) < 2
+
+
+@pytest.mark.parametrize(
+    "op",
+    [
+        lambda: ClassOne(2**31 - 1) + ClassOne(1),
+        lambda: ClassOne(-(2**31)) - ClassOne(1),
+        lambda: -ClassOne(-(2**31)),
+        lambda: abs(ClassOne(-(2**31))),
+    ],
+)
+def test_class_one_overflow(op):
+    with pytest.raises(OverflowError):
+        op()
+
+
+def test_tuple_clas_unpacks_like_a_one_tuple():
+    (a,) = TupleClas("x")
+    assert a == "x"
+    a, = TupleClas("y")
+    assert a == "y"
+    t = TupleClas("z")
+    assert len(t) == 1
+    assert t[0] == t[-1] == "z"
+    assert list(t) == ["z"]
+    with pytest.raises(IndexError):
+        t[1]
+    with pytest.raises(ValueError):
+        a, b = TupleClas("x")
+
+
+def test_from_string_returns_the_calling_class():
+    base = BaseClass.from_string(" 4 ")
+    assert type(base) is BaseClass
+    assert base.methoda() == 4
+    sub = SubClassA.from_string("5")
+    assert isinstance(sub, SubClassA)
+    assert isinstance(sub, BaseClass)
+    assert sub.full_id() == "base=5,sub=170"
+    with pytest.raises(ValueError):
+        BaseClass.from_string("five")
+
+
+def test_call_methoda_dispatches_to_python_overrides():
+    sentinel = object()
+
+    class Override(SubClassA):
+        def methoda(self):
+            return sentinel
+
+    assert matrix_mul.call_methoda(Override(1)) is sentinel
+    assert matrix_mul.call_methoda(SubClassA(3)) == 3
+    assert matrix_mul.call_methoda(BaseClass(4)) == 4
+
+
+@pytest.mark.parametrize("copier", [copy.copy, copy.deepcopy])
+def test_base_class_copies(copier):
+    original = BaseClass(4)
+    duplicate = copier(original)
+    assert type(duplicate) is BaseClass
+    assert duplicate is not original
+    assert duplicate.methoda() == 4
+
+
+@pytest.mark.parametrize("copier", [copy.copy, copy.deepcopy])
+def test_subclass_copies_are_independent(copier):
+    original = SubClassA(2)
+    duplicate = copier(original)
+    assert type(duplicate) is SubClassA
+    assert duplicate is not original
+    assert duplicate.full_id() == original.full_id() == "base=2,sub=68"
+    duplicate.id2 = 1
+    assert original.id2 == 68
+    original.id2 = 5
+    assert duplicate.id2 == 1
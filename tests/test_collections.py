@@ -0,0 +1,33 @@
+import pytest
+
+from matrix_mul.collections import Queue, Stack
+
+
+def test_queue_is_fifo():
+    q = Queue()
+    assert not q
+    for item in ("a", "b", "c"):
+        q.push(item)
+    assert list(q) == ["a", "b", "c"]
+    assert q.pop() == "a"
+    assert q.peek() == "b"
+    assert len(q) == 2
+
+
+def test_stack_is_lifo():
+    s = Stack()
+    assert not s
+    for item in ("a", "b", "c"):
+        s.push(item)
+    assert list(s) == ["c", "b", "a"]
+    assert s.pop() == "c"
+    assert s.peek() == "b"
+    assert len(s) == 2
+
+
+@pytest.mark.parametrize("cls", [Queue, Stack])
+def test_empty_raises_index_error(cls):
+    with pytest.raises(IndexError):
+        cls().pop()
+    with pytest.raises(IndexError):
+        cls().peek()
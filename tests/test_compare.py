@@ -0,0 +1,58 @@
+import pytest
+
+from matrix_mul import Matrix
+
+NAN = float("nan")
+INF = float("inf")
+
+
+def test_exact_equality():
+    assert Matrix([[1, 2]]) == Matrix([[1.0, 2.0]])
+    assert Matrix([[1, 2]]) != Matrix([[1, 2.000001]])
+    assert Matrix([[1, 2]]) != Matrix([[1], [2]])
+    assert Matrix([[1, 2]]) != [[1, 2]]
+    m = Matrix([[NAN]])
+    assert m != m
+
+
+@pytest.mark.parametrize(
+    "a, b, rtol, atol, close",
+    [
+        (1.0, 1.5, 0.0, 0.5, True),
+        (1.0, 1.5000001, 0.0, 0.5, False),
+        (3.0, 2.0, 0.5, 0.0, True),
+        (3.0000001, 2.0, 0.5, 0.0, False),
+        (1.0, 1.0 + 1e-9, 1e-5, 1e-8, True),
+        (INF, INF, 1e-5, 1e-8, True),
+        (INF, -INF, 1e-5, 1e-8, False),
+        (1e308, INF, 1e-5, 1e-8, False),
+    ],
+)
+def test_allclose_tolerance_boundaries(a, b, rtol, atol, close):
+    assert Matrix([[a]]).allclose(Matrix([[b]]), rtol=rtol, atol=atol) is close
+
+
+def test_allclose_is_relative_to_other():
+    # |a - b| <= atol + rtol * |b| is not symmetric, as in numpy
+    a, b = Matrix([[1.0]]), Matrix([[2.0]])
+    assert a.allclose(b, rtol=0.5, atol=0.0)
+    assert not b.allclose(a, rtol=0.5, atol=0.0)
+
+
+def test_allclose_nan_flag():
+    a, b = Matrix([[NAN, 1.0]]), Matrix([[NAN, 1.0]])
+    assert not a.allclose(b)
+    assert a.allclose(b, equal_nan=True)
+    assert not a.allclose(Matrix([[0.0, 1.0]]), equal_nan=True)
+
+
+def test_allclose_different_shapes():
+    assert not Matrix([[1, 2]]).allclose(Matrix([[1], [2]]))
+
+
+def test_hash():
+    assert hash(Matrix([[1, 2]])) == hash(Matrix([[1.0, 2.0]]))
+    assert hash(Matrix([[0.0]])) == hash(Matrix([[-0.0]]))
+    assert len({Matrix([[1, 2]]), Matrix([[1, 2]]), Matrix([[1], [2]])}) == 2
+    d = {Matrix.identity(2): "eye"}
+    assert d[Matrix([[1, 0], [0, 1]])] == "eye"
@@ -0,0 +1,48 @@
+import sys
+
+import pytest
+
+from matrix_mul import DimensionMismatchError, DType, Matrix
+
+
+def test_large_zeros():
+    m = Matrix.zeros(1000, 1000)
+    assert m.shape == (1000, 1000)
+    assert (m.rows, m.cols) == (1000, 1000)
+    for i, j in [(0, 0), (999, 999), (500, 3), (-1, 0)]:
+        assert m[i, j] == 0.0
+    assert m.norm() == 0.0
+
+
+def test_identity_ones_full():
+    assert Matrix.identity(3).to_list() == [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+    assert Matrix.ones(2, 3).to_list() == [[1.0] * 3] * 2
+    assert Matrix.full(2, 2, 7.5).to_list() == [[7.5, 7.5], [7.5, 7.5]]
+    assert Matrix.zeros(2, 2, dtype=DType.F32).dtype == DType.F32
+
+
+@pytest.mark.parametrize("rows, cols", [(0, 3), (3, 0), (-1, 2)])
+def test_non_positive_dimensions(rows, cols):
+    with pytest.raises(ValueError):
+        Matrix.zeros(rows, cols)
+    with pytest.raises(ValueError):
+        Matrix.full(rows, cols, 1.0)
+
+
+def test_overflowing_allocation():
+    with pytest.raises(ValueError, match="too many elements"):
+        Matrix.zeros(sys.maxsize, sys.maxsize)
+
+
+def test_hstack_vstack():
+    a = Matrix([[1, 2], [3, 4]])
+    b = Matrix([[5], [6]])
+    assert Matrix.hstack([a, b]).to_list() == [[1.0, 2.0, 5.0], [3.0, 4.0, 6.0]]
+    c = Matrix([[7, 8]])
+    assert Matrix.vstack([a, c]).to_list() == [[1.0, 2.0], [3.0, 4.0], [7.0, 8.0]]
+    with pytest.raises(DimensionMismatchError):
+        Matrix.hstack([a, c])
+    with pytest.raises(DimensionMismatchError):
+        Matrix.vstack([a, b])
+    with pytest.raises(ValueError):
+        Matrix.vstack([])
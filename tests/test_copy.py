@@ -0,0 +1,32 @@
+import copy
+
+import pytest
+
+from matrix_mul import DType, Matrix
+
+
+@pytest.mark.parametrize("copier", [copy.copy, copy.deepcopy])
+def test_copy_is_independent(copier):
+    a = Matrix([[1, 2], [3, 4]])
+    b = copier(a)
+    assert b == a
+    assert b is not a
+    b[0, 0] = 99
+    assert a[0, 0] == 1.0
+    a[1, 1] = -1
+    assert b[1, 1] == 4.0
+
+
+def test_copy_keeps_dtype():
+    a = Matrix([[1.5]], dtype=DType.F32)
+    assert copy.copy(a).dtype == DType.F32
+    assert copy.deepcopy(a).dtype == DType.F32
+
+
+def test_deepcopy_inside_containers():
+    a = Matrix([[1, 2]])
+    nested = {"m": a, "same": a}
+    copied = copy.deepcopy(nested)
+    copied["m"][0, 0] = 99
+    assert a[0, 0] == 1.0
+    assert copied["same"] is copied["m"]
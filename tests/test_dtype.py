@@ -0,0 +1,56 @@
+import math
+
+import pytest
+
+from matrix_mul import DType, Matrix
+
+ROWS = [[1.5, -2.0, 3.0], [4.0, 0.1, -6.0]]
+
+
+@pytest.mark.parametrize("dtype", [DType.F32, DType.F64])
+def test_row_col_and_norms(dtype):
+    m = Matrix(ROWS, dtype=dtype)
+    expected = Matrix(ROWS, dtype=dtype).to_list()
+    assert m.row(1) == expected[1]
+    assert m.col(2) == [3.0, -6.0]
+    assert m.norm(1) == 9.0
+    assert math.isclose(m.norm(math.inf), 10.1, rel_tol=1e-6)
+    assert math.isclose(m.norm(), math.sqrt(sum(x * x for r in expected for x in r)))
+
+
+def test_float32_rounds_but_compares_against_float64():
+    f32 = Matrix([[0.1]], dtype=DType.F32)
+    f64 = Matrix([[0.1]])
+    assert f32 != f64
+    assert f32.allclose(f64)
+    assert f32 == Matrix(f32.to_list())
+    assert hash(f32) == hash(Matrix(f32.to_list()))
+
+
+@pytest.mark.parametrize(
+    "a, b, result",
+    [
+        (DType.F32, DType.F32, DType.F32),
+        (DType.F32, DType.F64, DType.F64),
+        (DType.F64, DType.F32, DType.F64),
+    ],
+)
+def test_matmul_dtypes(a, b, result):
+    x = Matrix([[1, 2], [3, 4]], dtype=a)
+    y = Matrix([[5, 6], [7, 8]], dtype=b)
+    product = x @ y
+    assert product.dtype == result
+    assert product.to_list() == [[19.0, 22.0], [43.0, 50.0]]
+
+
+def test_large_float32_matmul_matches_float64():
+    a = Matrix.random(80, 70, seed=1)
+    b = Matrix.random(70, 90, seed=2)
+    a32, b32 = Matrix(a.to_list(), dtype=DType.F32), Matrix(b.to_list(), dtype=DType.F32)
+    assert (a32 @ b32).allclose(a @ b, rtol=1e-5, atol=1e-5)
+
+
+@pytest.mark.parametrize("dtype", ["int8", "float16", "f8"])
+def test_unknown_dtype(dtype):
+    with pytest.raises(ValueError, match="unknown dtype"):
+        Matrix.ones(2, 2, dtype=dtype)
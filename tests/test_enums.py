@@ -0,0 +1,38 @@
+import pytest
+
+from matrix_mul import SampleEnum
+
+
+def test_sample_enum_is_hashable():
+    assert len({SampleEnum.Firsty, SampleEnum.Secondy}) == 2
+    assert len({SampleEnum.Firsty, SampleEnum.Firsty}) == 1
+    assert {SampleEnum.Lastly: "last"}[SampleEnum.Lastly] == "last"
+
+
+def test_variants_in_declaration_order():
+    order = [SampleEnum.Firsty, SampleEnum.Secondy, SampleEnum.Lastly]
+    assert SampleEnum.variants() == order
+    assert SampleEnum.values() == order
+    assert [v.name for v in SampleEnum.variants()] == ["Firsty", "Secondy", "Lastly"]
+    assert [v.value for v in SampleEnum.variants()] == [0, 1, 10]
+    assert list(SampleEnum.__members__) == ["Firsty", "Secondy", "Lastly"]
+    assert SampleEnum.Firsty in SampleEnum.variants()
+
+
+@pytest.mark.parametrize(
+    "s, expected",
+    [
+        ("Firsty", SampleEnum.Firsty),
+        ("secondy", SampleEnum.Secondy),
+        ("lastly", SampleEnum.Lastly),
+        ("LASTLY", SampleEnum.Lastly),
+    ],
+)
+def test_from_str_ignores_case(s, expected):
+    assert SampleEnum.from_str(s) == expected
+
+
+@pytest.mark.parametrize("s", ["", "first", "Lastly ", "10"])
+def test_from_str_rejects_unknown_names(s):
+    with pytest.raises(ValueError):
+        SampleEnum.from_str(s)
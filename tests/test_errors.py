@@ -0,0 +1,96 @@
+import pickle
+from concurrent.futures import ProcessPoolExecutor
+
+import pytest
+
+import matrix_mul
+from matrix_mul import DimensionError, DimensionMismatchError, Matrix, MatrixError, SingularMatrixError
+
+
+def mismatch():
+    with pytest.raises(DimensionMismatchError) as excinfo:
+        Matrix([[1, 2]]) @ Matrix([[1, 2]])
+    return excinfo.value
+
+
+@pytest.mark.parametrize("cls", [MatrixError, DimensionMismatchError, SingularMatrixError])
+def test_module_and_plain_pickle(cls):
+    assert cls.__module__ == "matrix_mul"
+    restored = pickle.loads(pickle.dumps(cls("boom")))
+    assert type(restored) is cls
+    assert restored.args == ("boom",)
+
+
+def test_pickle_keeps_shapes():
+    restored = pickle.loads(pickle.dumps(mismatch()))
+    assert str(restored) == "cannot multiply 1x2 by 1x2"
+    assert (restored.lhs_shape, restored.rhs_shape) == ((1, 2), (1, 2))
+
+
+def test_error_crosses_a_process_pool():
+    with ProcessPoolExecutor(max_workers=1) as pool:
+        future = pool.submit(Matrix.__matmul__, Matrix([[1, 2, 3]]), Matrix([[1, 2]]))
+        with pytest.raises(DimensionMismatchError) as excinfo:
+            future.result()
+    assert excinfo.value.lhs_shape == (1, 3)
+    assert excinfo.value.rhs_shape == (1, 2)
+
+
+def test_hierarchy():
+    assert issubclass(MatrixError, ValueError)
+    assert issubclass(DimensionMismatchError, MatrixError)
+    assert issubclass(SingularMatrixError, MatrixError)
+    assert not issubclass(SingularMatrixError, DimensionMismatchError)
+
+
+def test_catch_subclasses_through_the_base():
+    with pytest.raises(MatrixError):
+        Matrix([[1, 2]]) @ Matrix([[1, 2]])
+    with pytest.raises(MatrixError):
+        Matrix([[1, 2], [2, 4]]).inverse()
+    try:
+        Matrix([[1, 2], [2, 4]]).inverse()
+    except MatrixError as err:
+        assert type(err) is SingularMatrixError
+
+
+def test_shape_attributes():
+    err = mismatch()
+    assert err.lhs_shape == (1, 2)
+    assert err.rhs_shape == (1, 2)
+    with pytest.raises(DimensionMismatchError) as excinfo:
+        Matrix([[1, 2, 3]]) + Matrix([[1], [2]])
+    assert (excinfo.value.lhs_shape, excinfo.value.rhs_shape) == ((1, 3), (2, 1))
+    # errors not about a pair of operands carry no shapes
+    with pytest.raises(DimensionMismatchError) as excinfo:
+        Matrix([[1, 2, 3]]).det()
+    assert excinfo.value.lhs_shape is None
+    assert DimensionMismatchError("plain").rhs_shape is None
+
+
+def test_dimension_error_alias():
+    assert DimensionError is DimensionMismatchError
+    assert matrix_mul.DimensionError is matrix_mul.DimensionMismatchError
+    with pytest.raises(DimensionError) as excinfo:
+        Matrix([[1, 2, 3]]) @ Matrix([[1, 2]])
+    assert (excinfo.value.lhs_shape, excinfo.value.rhs_shape) == ((1, 3), (1, 2))
+    restored = pickle.loads(pickle.dumps(excinfo.value))
+    assert isinstance(restored, DimensionError)
+    assert (restored.lhs_shape, restored.rhs_shape) == ((1, 3), (1, 2))
+
+
+def test_custom_error():
+    assert matrix_mul.CustomError.__module__ == "matrix_mul"
+    assert issubclass(matrix_mul.CustomError, MatrixError)
+    assert not issubclass(matrix_mul.CustomError, OSError)
+    with pytest.raises(matrix_mul.CustomError):
+        matrix_mul.raise_custom()
+    try:
+        matrix_mul.raise_custom()
+    except OSError:
+        pytest.fail("CustomError was caught as an OSError")
+    except matrix_mul.CustomError as err:
+        assert type(err) is matrix_mul.CustomError
+    with pytest.raises(matrix_mul.CustomError):
+        matrix_mul.risky_divide(1.0, 0.0)
+    assert matrix_mul.risky_divide(1.0, 4.0) == 0.25
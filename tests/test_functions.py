@@ -0,0 +1,129 @@
+import math
+import pathlib
+import re
+
+import pytest
+
+import matrix_mul
+from matrix_mul import functions
+
+
+@pytest.mark.parametrize(
+    "a, b",
+    [
+        (5, 6),
+        (-7, 3),
+        (-(2**63), -1),
+        (2**100, 1),
+        (2**64, 2**64),
+        (2**70, -(2**70) - 1),
+        (-(2**80), 2**63),
+    ],
+)
+def test_sum_as_string_matches_python(a, b):
+    assert matrix_mul.sum_as_string(a, b) == str(a + b)
+
+
+
+@pytest.mark.parametrize("x", [2**63, 2**62, -(2**62), 2**100])
+def test_triple_overflows(x):
+    with pytest.raises(OverflowError):
+        matrix_mul.triple(x)
+
+
+@pytest.mark.parametrize(
+    "x, expected",
+    [
+        (5, 15),
+        (-5, -15),
+        (2**62, 2**63 - 1),
+        (2**63, 2**63 - 1),
+        (2**100, 2**63 - 1),
+        (-(2**62), -(2**63)),
+        (-(2**100), -(2**63)),
+    ],
+)
+def test_triple_saturating_clamps(x, expected):
+    assert matrix_mul.triple_saturating(x) == expected
+
+
+@pytest.mark.parametrize(
+    "n, expected",
+    [(0, []), (1, []), (2, [2]), (97, [97]), (360, [2, 2, 2, 3, 3, 5]), (2**32 + 1, [641, 6700417])],
+)
+def test_prime_factors(n, expected):
+    assert functions.prime_factors(n) == expected
+
+
+def test_prime_factors_rejects_negatives():
+    with pytest.raises(OverflowError):
+        functions.prime_factors(-1)
+
+
+def test_return_either_or():
+    assert functions.return_either_or() is None
+    assert functions.return_either_or(None) is None
+    assert functions.return_either_or(5) == 5
+    assert functions.return_either_or(num=-3) == -3
+
+
+def test_version_matches_cargo():
+    cargo = pathlib.Path(__file__).resolve().parent.parent / "Cargo.toml"
+    # the first `version = ...` is the one in [package]
+    version = re.search(r'^version = "(.+)"$', cargo.read_text(), re.MULTILINE).group(1)
+    assert matrix_mul.__version__ == matrix_mul.version() == version
+    assert ".".join(map(str, matrix_mul.version_info())) == version
+
+
+@pytest.mark.parametrize(
+    "x, expected", [(-1.0, 0.0), (0.5, 0.5), (2.0, 1.0), (float("inf"), 1.0), (float("-inf"), 0.0)]
+)
+def test_clamp(x, expected):
+    assert functions.clamp(x, 0.0, 1.0) == expected
+
+
+def test_clamp_nan():
+    assert math.isnan(functions.clamp(float("nan"), 0.0, 1.0))
+    with pytest.raises(ValueError):
+        functions.clamp(0.5, float("nan"), 1.0)
+    with pytest.raises(ValueError):
+        functions.clamp(0.5, 0.0, float("nan"))
+
+
+def test_check_positive_float():
+    functions.check_positive_float(0.0)
+    functions.check_positive_float(-1e-12, epsilon=1e-9)
+    with pytest.raises(ValueError):
+        functions.check_positive_float(-1e-12)
+    with pytest.raises(ValueError):
+        functions.check_positive_float(float("nan"))
+
+
+@pytest.mark.parametrize("epsilon", [-1e-9, -1.0, float("nan")])
+def test_check_positive_float_rejects_bad_epsilon(epsilon):
+    with pytest.raises(ValueError, match="epsilon"):
+        functions.check_positive_float(1.0, epsilon=epsilon)
+
+
+def test_check_range():
+    assert functions.check_range(5, min=0, max=10) == 5
+    assert functions.check_range(0.5, min=0, inclusive=False) == 0.5
+    with pytest.raises(ValueError, match="NaN"):
+        functions.check_range(float("nan"), min=0)
+    with pytest.raises(ValueError):
+        functions.check_range(0, min=0, inclusive=False)
+    functions.check_positive(1)
+    with pytest.raises(ValueError):
+        functions.check_positive(-1)
+
+
+def test_triple_int_and_float():
+    assert matrix_mul.triple_int(5) == 15
+    with pytest.raises(OverflowError):
+        matrix_mul.triple_int(2**62)
+    with pytest.raises(OverflowError):
+        matrix_mul.triple_int(2**63)
+    assert matrix_mul.triple_float(1 / 3) == "1.000000"
+    assert matrix_mul.triple_float(1 / 3, 2) == "1.00"
+    with pytest.raises(OverflowError):
+        matrix_mul.triple_float(1e308)
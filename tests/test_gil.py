@@ -0,0 +1,82 @@
+import sys
+import threading
+import time
+
+from matrix_mul import Matrix, matmul
+
+
+def progress_during(call):
+    """how far a counter thread gets while `call` runs on this thread
+
+    The switch interval is raised so this thread never hands the GIL over on a
+    timer: the counter can only run while `call` has released it. The counter
+    drops the GIL itself after every step so this thread gets it back promptly.
+    """
+    count = 0
+    running = threading.Event()
+    stop = threading.Event()
+
+    def counter():
+        nonlocal count
+        running.set()
+        while not stop.is_set():
+            count += 1
+            time.sleep(0)
+
+    interval = sys.getswitchinterval()
+    sys.setswitchinterval(1.0)
+    worker = threading.Thread(target=counter)
+    try:
+        worker.start()
+        running.wait()
+        before = count
+        call()
+        after = count
+    finally:
+        stop.set()
+        worker.join()
+        sys.setswitchinterval(interval)
+    return after - before
+
+
+def hold_gil():
+    # pure Python, so the GIL stays held for the whole loop
+    total = 0
+    for i in range(3_000_000):
+        total += i
+
+
+def test_counter_stalls_while_the_gil_is_held():
+    assert progress_during(hold_gil) == 0
+
+
+def test_counter_advances_during_matmul():
+    a = Matrix.random(400, 400, seed=1)
+    assert progress_during(lambda: a @ a) > 0
+
+
+def test_threads_sharing_matrices():
+    threads, iterations = 8, 25
+    a = Matrix([[1, 2], [3, 4]])
+    b = Matrix([[5, 6], [7, 8]])
+    expected = [[19.0, 22.0], [43.0, 50.0]]
+    lock = threading.Lock()
+    total = 0
+    wrong = []
+
+    def work():
+        nonlocal total
+        for _ in range(iterations):
+            product = matmul(a, b)
+            if product.to_list() != expected:
+                wrong.append(product)
+            with lock:
+                total += 1
+
+    workers = [threading.Thread(target=work) for _ in range(threads)]
+    for worker in workers:
+        worker.start()
+    for worker in workers:
+        worker.join()
+    assert total == threads * iterations
+    assert wrong == []
@@ -0,0 +1,83 @@
+import math
+
+import pytest
+
+from matrix_mul import DimensionMismatchError, Matrix, SingularMatrixError, Vector, dot
+
+
+def random_invertible(n, seed):
+    # diagonally dominant, so always invertible
+    return Matrix.random(n, n, seed=seed) + Matrix.identity(n) * n
+
+
+@pytest.mark.parametrize("n", [1, 2, 5, 20])
+def test_inverse_times_original_is_identity(n):
+    m = random_invertible(n, seed=n)
+    assert m.matmul(m.inverse()).allclose(Matrix.identity(n), atol=1e-9)
+    assert (m.inverse() @ m).allclose(Matrix.identity(n), atol=1e-9)
+
+
+def test_determinant():
+    assert Matrix([[4, 7], [2, 6]]).det() == pytest.approx(10.0)
+    assert Matrix([[2, 0, 1], [1, 3, 2], [1, 1, 2]]).determinant() == pytest.approx(6.0)
+    assert Matrix([[0, 1], [1, 0]]).det() == pytest.approx(-1.0)
+    m = random_invertible(6, seed=3)
+    assert m.det() * m.inverse().det() == pytest.approx(1.0)
+
+
+def test_singular_and_non_square():
+    singular = Matrix([[1, 2], [2, 4]])
+    assert singular.det() == 0.0
+    with pytest.raises(SingularMatrixError):
+        singular.inverse()
+    with pytest.raises(SingularMatrixError):
+        singular.solve([1.0, 2.0])
+    with pytest.raises(DimensionMismatchError):
+        Matrix([[1, 2, 3]]).det()
+    with pytest.raises(DimensionMismatchError):
+        Matrix([[1, 2, 3]]).inverse()
+
+
+def test_solve():
+    m = random_invertible(8, seed=4)
+    x = [float(i) for i in range(8)]
+    b = (m @ Matrix([[v] for v in x])).col(0)
+    assert m.solve(b) == pytest.approx(x)
+    solved = m.solve(Vector(b))
+    assert isinstance(solved, Vector)
+    assert solved.components == pytest.approx(x)
+    with pytest.raises(DimensionMismatchError):
+        m.solve([1.0, 2.0])
+
+
+def test_norms():
+    m = Matrix([[1, -2], [-3, 4]])
+    assert m.norm() == pytest.approx(math.sqrt(30))
+    assert m.norm("fro") == m.norm()
+    assert m.norm(1) == 6.0
+    assert m.norm(math.inf) == 7.0
+    with pytest.raises(ValueError):
+        m.norm(2)
+
+
+def test_power():
+    m = Matrix([[1, 1], [1, 0]])
+    assert m ** 3 == m @ m @ m
+    assert (m ** 10).to_list() == [[89.0, 55.0], [55.0, 34.0]]
+    assert m ** 0 == Matrix.identity(2)
+    assert m ** 1 == m
+    r = Matrix.random(6, 6, seed=7)
+    assert (r ** 5).allclose(r @ r @ r @ r @ r)
+
+
+def test_power_errors():
+    with pytest.raises(ValueError):
+        Matrix([[1, 2]]) ** 2
+    with pytest.raises(ValueError):
+        Matrix([[1, 2], [3, 4]]) ** -1
+
+
+def test_dot():
+    assert dot([1, 2, 3], [4, 5, 6]) == 32.0
+    with pytest.raises(DimensionMismatchError):
+        dot([1], [1, 2])
@@ -0,0 +1,65 @@
+import threading
+
+import pytest
+
+from matrix_mul import DType, Matrix
+
+
+def fail_on_three(x):
+    if x == 3:
+        raise KeyError("bad element")
+    return x
+
+
+def test_map_square():
+    m = Matrix([[1, 2], [3, 4]])
+    assert m.map(lambda x: x**2).to_list() == [[1.0, 4.0], [9.0, 16.0]]
+    assert m.to_list() == [[1.0, 2.0], [3.0, 4.0]]
+    assert Matrix([[2]], dtype=DType.F32).map(lambda x: x**2).dtype == DType.F32
+
+
+def test_map_inplace_square():
+    m = Matrix([[1, 2], [3, 4]])
+    assert m.map_inplace(lambda x: x**2) is None
+    assert m.to_list() == [[1.0, 4.0], [9.0, 16.0]]
+
+
+def test_raising_callable_propagates_with_position():
+    m = Matrix([[1, 2], [3, 4]])
+    with pytest.raises(KeyError) as excinfo:
+        m.map(fail_on_three)
+    assert excinfo.value.__notes__ == ["while mapping element (1, 0)"]
+    with pytest.raises(KeyError):
+        m.map_inplace(fail_on_three)
+    # nothing is written back when any call fails
+    assert m.to_list() == [[1.0, 2.0], [3.0, 4.0]]
+
+
+def test_non_numeric_result():
+    with pytest.raises(TypeError, match=r"element \(0, 1\)"):
+        Matrix([[1, 2]]).map(lambda x: "two" if x == 2 else x)
+
+
+def test_map_inplace_callable_reads_the_matrix():
+    m = Matrix([[1, 2], [3, 4]])
+    m.map_inplace(lambda x: x + m[0, 0])
+    assert m.to_list() == [[2.0, 3.0], [4.0, 5.0]]
+
+
+def test_map_inplace_while_another_thread_reads():
+    m = Matrix.random(400, 400, seed=3)
+    worker = threading.Thread(target=lambda: m @ m)
+    worker.start()
+    refused = 0
+    try:
+        while worker.is_alive():
+            try:
+                m.map_inplace(lambda x: x)
+            except RuntimeError:
+                refused += 1
+    finally:
+        worker.join()
+    assert refused > 0
+    first = m[0, 0]
+    m.map_inplace(lambda x: -x)
+    assert m[0, 0] == -first
@@ -0,0 +1,58 @@
+import pytest
+
+from matrix_mul import DimensionMismatchError, Matrix, matmul, matmul_parallel
+
+
+def test_square():
+    assert matmul([[1, 2], [3, 4]], [[5, 6], [7, 8]]) == [[19.0, 22.0], [43.0, 50.0]]
+
+
+def test_rectangular():
+    a = [[1, 2, 3], [4, 5, 6]]
+    b = [[7, 8], [9, 10], [11, 12]]
+    assert matmul(a, b) == [[58.0, 64.0], [139.0, 154.0]]
+
+
+def test_row_times_column_and_column_times_row():
+    assert matmul([[1, 2, 3]], [[4], [5], [6]]) == [[32.0]]
+    assert matmul([[1], [2]], [[3, 4]]) == [[3.0, 4.0], [6.0, 8.0]]
+
+
+def test_matrix_operands_give_a_matrix():
+    product = matmul(Matrix([[1, 2]]), Matrix([[3], [4]]))
+    assert isinstance(product, Matrix)
+    assert product.to_list() == [[11.0]]
+
+
+@pytest.mark.parametrize("fn", [matmul, matmul_parallel])
+def test_mixed_operands_give_a_matrix(fn):
+    for a, b in [(Matrix([[1, 2]]), [[3], [4]]), ([[1, 2]], Matrix([[3], [4]]))]:
+        product = fn(a, b)
+        assert isinstance(product, Matrix)
+        assert product.to_list() == [[11.0]]
+    assert fn([[1, 2]], [[3], [4]]) == [[11.0]]
+
+
+def test_mismatched_shapes():
+    with pytest.raises(DimensionMismatchError) as excinfo:
+        matmul([[1, 2, 3]], [[1, 2, 3]])
+    assert "1x3" in str(excinfo.value)
+    assert isinstance(excinfo.value, ValueError)
+
+
+def test_ragged_rows():
+    with pytest.raises(ValueError):
+        matmul([[1, 2], [3]], [[1], [2]])
+
+
+def test_parallel_matches_serial():
+    a = Matrix.random(300, 200, seed=1)
+    b = Matrix.random(200, 250, seed=2)
+    assert matmul_parallel(a, b) == matmul(a, b)
+    assert matmul_parallel(a, b, num_threads=3) == matmul(a, b)
+
+
+@pytest.mark.parametrize("num_threads", [0, -1])
+def test_parallel_rejects_non_positive_thread_counts(num_threads):
+    with pytest.raises(ValueError):
+        matmul_parallel([[1.0]], [[1.0]], num_threads=num_threads)
@@ -0,0 +1,70 @@
+import pytest
+
+np = pytest.importorskip("numpy")
+
+from matrix_mul import DimensionMismatchError, DType, Matrix, from_numpy, matmul_np  # noqa: E402
+
+
+@pytest.mark.parametrize("dtype, expected", [(np.float64, DType.F64), (np.float32, DType.F32)])
+def test_from_numpy_keeps_dtype(dtype, expected):
+    arr = np.arange(6, dtype=dtype).reshape(2, 3)
+    m = from_numpy(arr)
+    assert m.dtype == expected
+    assert m.to_list() == arr.tolist()
+
+
+def test_from_numpy_rejects_other_dtypes():
+    with pytest.raises(TypeError):
+        from_numpy(np.arange(6).reshape(2, 3))
+
+
+@pytest.mark.parametrize("dtype, np_dtype", [(DType.F64, np.float64), (DType.F32, np.float32)])
+def test_to_numpy_is_a_read_only_view(dtype, np_dtype):
+    m = Matrix([[1, 2], [3, 4]], dtype=dtype)
+    arr = m.to_numpy()
+    assert arr.dtype == np_dtype
+    assert not arr.flags.writeable
+    with pytest.raises(ValueError):
+        arr[0, 0] = 9.0
+    m[0, 0] = 9.0
+    assert arr[0, 0] == 9.0
+    with pytest.raises(BufferError):
+        m.transpose(in_place=True)
+    del arr
+    m.transpose(in_place=True)
+
+
+def test_numpy_roundtrip():
+    eye = np.eye(3)
+    np.testing.assert_array_equal(from_numpy(eye).to_numpy(), eye)
+
+
+def layouts():
+    base = np.arange(1.0, 33.0).reshape(4, 8)
+    return {
+        "c": np.ascontiguousarray(base[:, :4]),
+        "fortran": np.asfortranarray(base[:, :4]),
+        # every other column, so neither C- nor Fortran-contiguous
+        "strided": base[:, ::2],
+    }
+
+
+@pytest.mark.parametrize("layout", ["c", "fortran", "strided"])
+def test_from_numpy_layouts(layout):
+    arr = layouts()[layout]
+    assert from_numpy(arr).to_list() == arr.tolist()
+
+
+@pytest.mark.parametrize("layout", ["c", "fortran", "strided"])
+def test_matmul_np_layouts(layout):
+    a = layouts()[layout]
+    b = np.asfortranarray(np.arange(1.0, a.shape[1] * 2 + 1).reshape(a.shape[1], 2))
+    np.testing.assert_allclose(matmul_np(a, b), a @ b)
+    np.testing.assert_allclose(matmul_np(a[::2], b[::-1]), a[::2] @ b[::-1])
+
+
+def test_matmul_np_errors():
+    with pytest.raises(DimensionMismatchError):
+        matmul_np(np.ones((2, 3)), np.ones((2, 3)))
+    with pytest.raises(TypeError):
+        matmul_np(np.ones((2, 2), dtype=np.float32), np.ones((2, 2)))
@@ -0,0 +1,61 @@
+import copy
+import pickle
+from concurrent.futures import ProcessPoolExecutor
+
+import pytest
+
+from matrix_mul import BaseClass, ClassOne, DType, Matrix, SubClassA
+
+
+def roundtrip(obj):
+    return pickle.loads(pickle.dumps(obj))
+
+
+def test_class_one():
+    restored = roundtrip(ClassOne(3))
+    assert type(restored) is ClassOne
+    assert restored.id == 3
+
+
+def test_base_class():
+    restored = roundtrip(BaseClass(5))
+    assert type(restored) is BaseClass
+    assert restored.methoda() == BaseClass(5).methoda()
+    assert restored.__getstate__() == 5
+
+
+def test_subclass_keeps_both_levels():
+    original = SubClassA(3)
+    original.__setstate__((7, 11))
+    restored = roundtrip(original)
+    assert type(restored) is SubClassA
+    assert restored.__getstate__() == (7, 11)
+    assert (restored.id, restored.id2) == (original.id, original.id2)
+
+
+def test_matrix():
+    original = Matrix([[1, 2, 3], [4, 5, 6]], dtype=DType.F32)
+    restored = roundtrip(original)
+    assert restored == original
+    assert restored.dtype == DType.F32
+
+
+@pytest.mark.parametrize("state", ["x", None, (1,), (1, 2, 3)])
+def test_bad_state_raises(state):
+    with pytest.raises(TypeError):
+        ClassOne(1).__setstate__(state)
+    with pytest.raises(TypeError):
+        SubClassA(1).__setstate__(state)
+
+
+def test_process_pool_roundtrip():
+    m = Matrix([[1, 2], [3, 4]])
+    sub = SubClassA(3)
+    with ProcessPoolExecutor(max_workers=1) as pool:
+        # copy.deepcopy runs in the worker, so each object is pickled there
+        # and back
+        assert pool.submit(copy.deepcopy, m).result() == m
+        assert pool.submit(copy.deepcopy, ClassOne(4)).result().id == 4
+        restored = pool.submit(copy.deepcopy, sub).result()
+        assert restored.__getstate__() == sub.__getstate__()
+        assert pool.submit(Matrix.__matmul__, m, m).result() == m @ m
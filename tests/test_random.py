@@ -0,0 +1,42 @@
+import pytest
+
+from matrix_mul import Matrix, set_default_seed
+
+
+def test_same_seed_same_matrix():
+    assert Matrix.random(5, 4, seed=42) == Matrix.random(5, 4, seed=42)
+    assert Matrix.randn(5, 4, seed=42) == Matrix.randn(5, 4, seed=42)
+    assert Matrix.random(5, 4, seed=42) != Matrix.random(5, 4, seed=43)
+
+
+def test_seeded_values_are_pinned():
+    # PCG32 is fully specified, so these hold on every platform
+    assert Matrix.random(1, 2, seed=1).to_list() == [[0.3289364276959148, 0.028943854490840804]]
+    assert Matrix.randn(1, 3, seed=1).row(0) == pytest.approx(
+        [-1.6701686246661576, 0.19004037053802894, 0.8333073911434664]
+    )
+
+
+def test_bounds():
+    m = Matrix.random(50, 50, seed=3, low=-2.0, high=-1.0)
+    assert all(-2.0 <= x < -1.0 for row in m for x in row)
+    with pytest.raises(ValueError):
+        Matrix.random(2, 2, low=1.0, high=1.0)
+    with pytest.raises(ValueError):
+        Matrix.random(2, 2, low=2.0, high=1.0)
+
+
+def test_default_seed():
+    try:
+        set_default_seed(7)
+        first = [Matrix.random(3, 3), Matrix.randn(3, 3)]
+        set_default_seed(7)
+        second = [Matrix.random(3, 3), Matrix.randn(3, 3)]
+        assert first == second
+        # the calls share one stream, so consecutive draws differ
+        assert first[0] != Matrix.random(3, 3)
+        # an explicit seed ignores the default generator
+        assert Matrix.random(3, 3, seed=1) == Matrix.random(3, 3, seed=1)
+    finally:
+        set_default_seed(None)
+    assert Matrix.random(3, 3) != Matrix.random(3, 3)
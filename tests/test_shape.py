@@ -0,0 +1,62 @@
+import math
+
+import pytest
+
+from matrix_mul import Shape
+
+
+def test_area_perimeter_and_repr():
+    circle = Shape.circle(2.0)
+    assert math.isclose(circle.area(), 4 * math.pi, rel_tol=1e-6)
+    assert math.isclose(circle.perimeter(), 4 * math.pi, rel_tol=1e-6)
+    assert repr(circle) == "Shape.Circle(radius=2.0)"
+    rectangle = Shape.Rectangle(width=3.0, height=4.0)
+    assert rectangle.area() == 12.0
+    assert rectangle.perimeter() == 14.0
+    assert repr(rectangle) == "Shape.Rectangle(width=3.0, height=4.0)"
+    assert math.isclose(Shape.triangle(3, 4, 5).area(), 6.0, rel_tol=1e-6)
+
+
+@pytest.mark.parametrize(
+    "shape",
+    [
+        Shape.Circle(radius=-1.0),
+        Shape.Rectangle(width=float("nan"), height=1.0),
+        Shape.Triangle(a=1, b=1, c=10),
+    ],
+)
+def test_invalid_variant_constructors_fail_on_use(shape):
+    with pytest.raises(ValueError):
+        shape.area()
+    with pytest.raises(ValueError):
+        shape.perimeter()
+    with pytest.raises(ValueError):
+        shape.scale(2.0)
+    with pytest.raises(ValueError):
+        shape.contains_point(0, 0)
+    with pytest.raises(ValueError):
+        shape.bounding_box()
+    with pytest.raises(ValueError):
+        shape.to_dict()
+
+
+@pytest.mark.parametrize(
+    "build", [lambda: Shape.circle(-1.0), lambda: Shape.triangle(1, 1, 10)]
+)
+def test_validated_constructors_reject_up_front(build):
+    with pytest.raises(ValueError):
+        build()
+
+
+@pytest.mark.parametrize(
+    "shape", [Shape.circle(1.5), Shape.rectangle(2, 3), Shape.triangle(3, 4, 5)]
+)
+def test_dict_roundtrip(shape):
+    assert Shape.from_dict(shape.to_dict()) == shape
+
+
+def test_from_dict_errors():
+    with pytest.raises(KeyError):
+        Shape.from_dict({"kind": "circle"})
+    with pytest.raises(ValueError):
+        Shape.from_dict({"kind": "hexagon"})
@@ -0,0 +1,88 @@
+import threading
+
+import pytest
+
+from matrix_mul import DimensionMismatchError, Matrix
+
+
+def grid():
+    # m[i, j] == 4 * i + j
+    return Matrix([[4 * i + j for j in range(4)] for i in range(4)])
+
+
+def test_int_indices():
+    m = grid()
+    assert m[1, 2] == 6.0
+    assert m[-1, -1] == 15.0
+    assert m[-4, 0] == 0.0
+    for index in [(4, 0), (0, 4), (-5, 0), (0, -5)]:
+        with pytest.raises(IndexError):
+            m[index]
+
+
+def test_rows_and_columns():
+    m = grid()
+    assert m[1, :] == [4.0, 5.0, 6.0, 7.0]
+    assert m[:, 2] == [2.0, 6.0, 10.0, 14.0]
+    assert m[-1, ::2] == [12.0, 14.0]
+
+
+def test_sub_matrices():
+    m = grid()
+    assert m[1:3, :].to_list() == [[4.0, 5.0, 6.0, 7.0], [8.0, 9.0, 10.0, 11.0]]
+    assert m[::2, ::-1].to_list() == [[3.0, 2.0, 1.0, 0.0], [11.0, 10.0, 9.0, 8.0]]
+    assert m[-2:, -3:-1].to_list() == [[9.0, 10.0], [13.0, 14.0]]
+    assert m[::-3, 1:2].to_list() == [[13.0], [1.0]]
+
+
+def test_out_of_range_slices_clamp():
+    m = grid()
+    assert m[1:100, 3:].to_list() == [[7.0], [11.0], [15.0]]
+    assert m[10:, :2].shape == (0, 2)
+
+
+def test_assign_scalar_and_rows():
+    m = grid()
+    m[0:2, 0:2] = [[-1, -2], [-3, -4]]
+    assert m[0, :] == [-1.0, -2.0, 2.0, 3.0]
+    assert m[1, :] == [-3.0, -4.0, 6.0, 7.0]
+    m[:, 3] = 0
+    assert m[:, 3] == [0.0] * 4
+    m[::3, 0] = Matrix([[5], [6]])
+    assert m[:, 0] == [5.0, -3.0, 8.0, 6.0]
+    m[-1, -1] = 99
+    assert m[3, 3] == 99.0
+
+
+@pytest.mark.parametrize(
+    "index, value",
+    [
+        ((slice(0, 2), slice(0, 2)), [[1, 2, 3]]),
+        ((slice(0, 2), slice(0, 2)), Matrix([[1, 2]])),
+        ((slice(None), 0), Matrix([[1], [2]])),
+        ((slice(None, None, 2), slice(None)), [[1, 2, 3, 4]]),
+    ],
+)
+def test_assign_shape_mismatch(index, value):
+    m = grid()
+    with pytest.raises(DimensionMismatchError):
+        m[index] = value
+    assert m == grid()
+
+
+def test_assign_while_another_thread_reads():
+    m = Matrix.random(400, 400, seed=1)
+    worker = threading.Thread(target=lambda: m @ m)
+    worker.start()
+    refused = 0
+    try:
+        while worker.is_alive():
+            try:
+                m[0:1, 0:1] = [[1.0]]
+            except RuntimeError:
+                refused += 1
+    finally:
+        worker.join()
+    assert refused > 0
+    m[0:1, 0:1] = [[2.0]]
+    assert m[0, 0] == 2.0
@@ -0,0 +1,47 @@
+import random
+
+import pytest
+
+from matrix_mul import DimensionMismatchError, Matrix, SparseMatrix
+
+
+def random_sparse(rng, rows, cols, density):
+    entries = {
+        (i, j): rng.uniform(-1, 1)
+        for i in range(rows)
+        for j in range(cols)
+        if rng.random() < density
+    }
+    return SparseMatrix(entries, shape=(rows, cols))
+
+
+@pytest.mark.parametrize("seed", range(5))
+def test_sparse_times_dense_matches_dense(seed):
+    rng = random.Random(seed)
+    sparse = random_sparse(rng, 30, 20, 0.2)
+    dense = Matrix.random(20, 15, seed=seed)
+    product = sparse @ dense
+    assert isinstance(product, Matrix)
+    assert product.allclose(sparse.to_dense() @ dense)
+
+
+@pytest.mark.parametrize("seed", range(5))
+def test_sparse_times_sparse_matches_dense(seed):
+    rng = random.Random(seed)
+    a = random_sparse(rng, 25, 30, 0.1)
+    b = random_sparse(rng, 30, 10, 0.1)
+    product = a @ b
+    assert isinstance(product, SparseMatrix)
+    assert product.to_dense().allclose(a.to_dense() @ b.to_dense())
+
+
+def test_from_dense_roundtrip():
+    m = Matrix([[0, 1, 0], [2, 0, 3]])
+    sparse = SparseMatrix.from_dense(m)
+    assert sparse.nnz == 3
+    assert sparse.to_dense() == m
+
+
+def test_mismatched_shapes():
+    with pytest.raises(DimensionMismatchError):
+        SparseMatrix({(0, 0): 1.0}, shape=(2, 3)) @ Matrix.ones(2, 2)
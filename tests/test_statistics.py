@@ -0,0 +1,54 @@
+import math
+import random
+import statistics as py_stats
+import warnings
+
+import pytest
+
+from matrix_mul import statistics
+
+
+_rng = random.Random(24)
+DATA = [_rng.uniform(-1e3, 1e3) for _ in range(1001)]
+
+
+def test_against_python_statistics():
+    data = DATA
+    assert math.isclose(statistics.mean(data), py_stats.mean(data))
+    assert statistics.median(data) == py_stats.median(data)
+    assert statistics.median(data[:-1]) == py_stats.median(data[:-1])
+    assert math.isclose(statistics.variance(data), py_stats.pvariance(data))
+    assert math.isclose(statistics.variance(data, ddof=1), py_stats.variance(data))
+    assert math.isclose(statistics.stddev(data), py_stats.pstdev(data))
+    assert math.isclose(statistics.stddev(data, ddof=1), py_stats.stdev(data))
+
+
+def test_variance_is_stable_for_a_large_offset():
+    shifted = [x + 1e9 for x in DATA]
+    assert math.isclose(statistics.variance(shifted), py_stats.pvariance(shifted), rel_tol=1e-6)
+
+
+def test_quantile_interpolates():
+    assert statistics.quantile([1, 2, 3, 4], 0.5) == 2.5
+    assert statistics.quantile([4, 1, 3, 2], 0.0) == 1.0
+    assert statistics.quantile([4, 1, 3, 2], 1.0) == 4.0
+    with pytest.raises(ValueError):
+        statistics.quantile([1, 2], 1.5)
+
+
+def test_empty_and_non_numeric_input():
+    with pytest.raises(ValueError):
+        statistics.median([])
+    with pytest.raises(ValueError):
+        statistics.stddev([1.0], ddof=1)
+    with pytest.raises(TypeError, match="1"):
+        statistics.mean([1.0, "x"])
+
+
+def test_std_dev_is_a_deprecated_alias():
+    with warnings.catch_warnings(record=True) as caught:
+        warnings.simplefilter("always")
+        assert statistics.std_dev(DATA) == statistics.stddev(DATA)
+        assert statistics.std_dev(DATA, ddof=1) == statistics.stddev(DATA, ddof=1)
+    assert [w.category for w in caught] == [DeprecationWarning, DeprecationWarning]
+    assert "stddev" in str(caught[0].message)
@@ -0,0 +1,40 @@
+import os
+import subprocess
+import sys
+import textwrap
+
+import pytest
+
+
+def run_fresh(code):
+    """runs `code` in a new interpreter, so nothing is already imported"""
+    env = {**os.environ, "PYTHONPATH": os.pathsep.join(sys.path)}
+    return subprocess.run(
+        [sys.executable, "-c", textwrap.dedent(code)],
+        env=env,
+        capture_output=True,
+        text=True,
+    )
+
+
+@pytest.mark.parametrize(
+    "code",
+    [
+        """
+        from matrix_mul.functions import gcd
+        assert gcd(12, 18) == 6
+        """,
+        """
+        import matrix_mul.statistics
+        assert matrix_mul.statistics.mean([1, 2, 3]) == 2.0
+        """,
+        """
+        from matrix_mul.collections import *
+        import matrix_mul.functions as functions
+        assert functions.__name__ == "matrix_mul.functions"
+        """,
+    ],
+)
+def test_submodule_imports_in_fresh_interpreter(code):
+    result = run_fresh(code)
+    assert result.returncode == 0, result.stderr
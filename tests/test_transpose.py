@@ -0,0 +1,58 @@
+import threading
+
+import pytest
+
+from matrix_mul import Matrix
+
+
+def test_transpose_twice_gives_back_the_original():
+    m = Matrix([[1, 2, 3], [4, 5, 6]])
+    t = m.transpose()
+    assert t.shape == (3, 2)
+    assert t.to_list() == [[1.0, 4.0], [2.0, 5.0], [3.0, 6.0]]
+    assert t.transpose() == m
+    assert m.to_list() == [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]
+
+
+def test_trace():
+    assert Matrix([[1, 2], [3, 4]]).trace() == 5.0
+    assert Matrix.identity(7).trace() == 7.0
+
+
+def test_trace_of_non_square_raises():
+    with pytest.raises(ValueError):
+        Matrix([[1, 2, 3], [4, 5, 6]]).trace()
+
+
+def test_transpose_while_another_thread_reads():
+    m = Matrix.identity(150) * 2 + Matrix.ones(150, 150)
+    worker = threading.Thread(target=m.inverse)
+    worker.start()
+    try:
+        while worker.is_alive():
+            assert m.transpose().shape == (150, 150)
+    finally:
+        worker.join()
+
+
+def test_transpose_in_place():
+    m = Matrix([[1, 2, 3]])
+    assert m.transpose(in_place=True) is None
+    assert m.to_list() == [[1.0], [2.0], [3.0]]
+
+
+def test_transpose_in_place_while_another_thread_reads():
+    m = Matrix.random(400, 400, seed=2)
+    worker = threading.Thread(target=lambda: m @ m)
+    worker.start()
+    refused = 0
+    try:
+        while worker.is_alive():
+            try:
+                m.transpose(in_place=True)
+            except RuntimeError:
+                refused += 1
+    finally:
+        worker.join()
+    assert refused > 0
+    assert m.transpose(in_place=True) is None